@@ -0,0 +1,132 @@
+// Carga da lista de tarefas com um ano de histórico: 10 mil tarefas, 200 mil logs de tempo.
+// Compara a consulta atual (com índices) com a antiga, uma consulta de sessões por tarefa e sem índices.
+// Rodar com: cargo bench -p clockwise-core
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use clockwise_core::database::init_in_memory_database;
+use clockwise_core::tasks::load_tasks_with_sessions;
+use clockwise_core::time_logs::calculate_task_remaining_time;
+use clockwise_core::SystemClock;
+use rusqlite::Connection;
+
+const TASKS: i64 = 10_000;
+const LOGS_PER_TASK: i64 = 20;
+const SESSIONS_PER_TASK: i64 = 8;
+const PAGE_SIZE: u32 = 50;
+
+fn seed(conn: &mut Connection) {
+    let tx = conn.transaction().unwrap();
+    {
+        let mut task = tx.prepare(
+            "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at)
+             VALUES (?1, 'bench', 2.0, ?2, ?3, ?4)"
+        ).unwrap();
+        let mut log = tx.prepare(
+            "INSERT INTO task_time_logs (task_id, started_at, ended_at) VALUES (?1, ?2, ?3)"
+        ).unwrap();
+        let mut session = tx.prepare(
+            "INSERT INTO pomodoro_sessions (task_id, session_number, session_type, duration_seconds, created_at, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'completed')"
+        ).unwrap();
+
+        for n in 0..TASKS {
+            let day = chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap() + chrono::Duration::days(n % 365);
+            let created_at = format!("{}T08:00:00+00:00", day);
+            let status = if n % 10 == 0 { "pending" } else { "completed" };
+            task.execute(rusqlite::params![format!("tarefa {}", n), day.to_string(), status, created_at]).unwrap();
+            let task_id = tx.last_insert_rowid();
+
+            for l in 0..LOGS_PER_TASK {
+                let started = format!("{}T{:02}:00:00+00:00", day, 8 + l % 12);
+                let ended = format!("{}T{:02}:05:00+00:00", day, 8 + l % 12);
+                log.execute(rusqlite::params![task_id, started, ended]).unwrap();
+            }
+
+            for s in 1..=SESSIONS_PER_TASK {
+                let (session_type, duration) = if s % 2 == 1 { ("work", 1500) } else { ("break", 300) };
+                session.execute(rusqlite::params![task_id, s, session_type, duration, created_at]).unwrap();
+            }
+        }
+    }
+    tx.commit().unwrap();
+    conn.execute_batch("ANALYZE").unwrap();
+}
+
+// Como era antes: a lista de tarefas e depois uma consulta de sessões para cada uma
+fn load_tasks_one_query_per_task(conn: &Connection) -> usize {
+    let mut stmt = conn.prepare("SELECT id FROM tasks ORDER BY scheduled_date ASC, created_at ASC").unwrap();
+    let ids: Vec<i64> = stmt.query_map([], |row| row.get(0)).unwrap().map(Result::unwrap).collect();
+
+    let mut total = 0;
+    for task_id in ids {
+        let mut sessions = conn.prepare(
+            "SELECT ps.id, ps.session_number, ps.session_type, ps.duration_seconds, ps.created_at,
+                    a.started_at, ps.status, ps.started_at, ps.ended_at, ps.elapsed_seconds
+             FROM pomodoro_sessions ps
+             LEFT JOIN active_sessions a ON ps.id = a.pomodoro_id AND ps.task_id = a.task_id
+             WHERE ps.task_id = ?1
+             ORDER BY ps.session_number ASC"
+        ).unwrap();
+        total += sessions.query_map([task_id], |row| row.get::<_, i64>(0)).unwrap().count();
+    }
+    total
+}
+
+fn remaining_time_for_first_tasks(conn: &Connection, count: i64) {
+    for task_id in 1..=count {
+        black_box(calculate_task_remaining_time(conn, &SystemClock, task_id, 2.0).unwrap());
+    }
+}
+
+fn measure(name: &str, runs: u32, mut f: impl FnMut()) -> Duration {
+    // Aquecimento só nas medições rápidas; as lentas já levam dezenas de segundos por rodada
+    if runs > 1 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..runs {
+        f();
+    }
+    let average = start.elapsed() / runs;
+    println!("{:<55} {:>10.2?}", name, average);
+    average
+}
+
+fn main() {
+    let mut conn = init_in_memory_database().unwrap();
+    let start = Instant::now();
+    seed(&mut conn);
+    println!("Banco com {} tarefas e {} logs criado em {:.2?}\n", TASKS, TASKS * LOGS_PER_TASK, start.elapsed());
+
+    let full = measure("lista completa (2 consultas, com índices)", 10, || {
+        black_box(load_tasks_with_sessions(&conn, None, 0).unwrap());
+    });
+    measure("uma página de 50 (2 consultas, com índices)", 50, || {
+        black_box(load_tasks_with_sessions(&conn, Some(PAGE_SIZE), 0).unwrap());
+    });
+    let remaining = measure("tempo restante de 1000 tarefas (com índices)", 3, || {
+        remaining_time_for_first_tasks(&conn, 1_000);
+    });
+
+    conn.execute_batch(
+        "DROP INDEX idx_task_time_logs_task_id;
+         DROP INDEX idx_pomodoro_sessions_task_id;
+         DROP INDEX idx_pomodoro_session_history_task_id;
+         DROP INDEX idx_tasks_scheduled_date;
+         DROP INDEX idx_active_sessions_pomodoro_id;
+         ANALYZE;"
+    ).unwrap();
+    println!();
+
+    let legacy = measure("lista completa (1 consulta por tarefa, sem índices)", 1, || {
+        black_box(load_tasks_one_query_per_task(&conn));
+    });
+    let remaining_unindexed = measure("tempo restante de 1000 tarefas (sem índices)", 1, || {
+        remaining_time_for_first_tasks(&conn, 1_000);
+    });
+
+    println!("\nLista: {:.0}x mais rápida; tempo restante: {:.0}x mais rápido",
+        legacy.as_secs_f64() / full.as_secs_f64(),
+        remaining_unindexed.as_secs_f64() / remaining.as_secs_f64());
+}
@@ -0,0 +1,379 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use rusqlite::{Connection, Result as SqliteResult};
+
+use crate::error::AppError;
+
+type Migration = fn(&Connection) -> SqliteResult<()>;
+
+// Migrações em ordem; a posição (1-based) é a versão gravada em PRAGMA user_version.
+// Nunca altere uma migração já publicada: adicione uma nova ao final.
+const SCHEMA_MIGRATIONS: &[(&str, Migration)] = &[
+    ("esquema inicial", migration_001_initial_schema),
+    ("estado das sessões Pomodoro", migration_002_session_state),
+    ("perfis Pomodoro", migration_003_pomodoro_profiles),
+    ("histórico de ações das sessões", migration_004_session_history),
+    ("configurações do app", migration_005_app_settings),
+    ("índices das consultas por tarefa e data", migration_006_query_indexes),
+    ("batimento dos logs de tempo abertos", migration_007_time_log_heartbeat),
+    ("extensões das sessões Pomodoro", migration_008_session_extensions),
+];
+
+// Sobrescreve o local do banco; a flag --database tem prioridade sobre a variável
+const DATABASE_PATH_ENV: &str = "CLOCKWISE_DB_PATH";
+
+const DATABASE_PATH_FLAG: &str = "--database";
+
+const DATABASE_FILE_NAME: &str = "tasks.db";
+
+const APP_IDENTIFIER: &str = "com.clockwise.panel";
+
+fn database_path_override() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == DATABASE_PATH_FLAG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--database=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    std::env::var_os(DATABASE_PATH_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+// Usado apenas se o Tauri não conseguir resolver o diretório de dados do app
+fn xdg_data_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+pub fn resolve_database_path(app_data_dir: Option<PathBuf>) -> Result<PathBuf, AppError> {
+    if let Some(path) = database_path_override() {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        return Ok(path);
+    }
+
+    let data_dir = app_data_dir
+        .or_else(xdg_data_dir)
+        .ok_or_else(|| AppError::System("Não foi possível determinar o diretório de dados do app".to_string()))?;
+    std::fs::create_dir_all(&data_dir)?;
+
+    let path = data_dir.join(DATABASE_FILE_NAME);
+    migrate_legacy_database(&path)?;
+
+    Ok(path)
+}
+
+// Versões anteriores gravavam ./tasks.db no diretório de trabalho; copiado uma única vez
+fn migrate_legacy_database(target: &Path) -> Result<(), AppError> {
+    let legacy = PathBuf::from(DATABASE_FILE_NAME);
+
+    if target.exists() || !legacy.is_file() {
+        return Ok(());
+    }
+
+    std::fs::copy(&legacy, target)
+        .map_err(|e| AppError::System(format!("Falha ao migrar {} para {}: {}", legacy.display(), target.display(), e)))?;
+
+    log::info!("Banco legado {} copiado para {} (o original foi mantido)",
+        legacy.display(), target.display());
+    Ok(())
+}
+
+pub fn init_database(path: &Path) -> Result<Connection, AppError> {
+    prepare_connection(Connection::open(path)?)
+}
+
+// Mesmo esquema e configuração do banco real, mas só em memória (testes e simulações)
+pub fn init_in_memory_database() -> Result<Connection, AppError> {
+    prepare_connection(Connection::open_in_memory()?)
+}
+
+fn prepare_connection(mut conn: Connection) -> Result<Connection, AppError> {
+    // Sem isso o SQLite ignora os ON DELETE CASCADE do esquema (vale por conexão)
+    conn.pragma_update(None, "foreign_keys", true)?;
+
+    migrate_database(&mut conn)?;
+    Ok(conn)
+}
+
+pub fn migrate_database(conn: &mut Connection) -> Result<(), AppError> {
+    let latest_version = SCHEMA_MIGRATIONS.len() as i64;
+    let current_version: i64 = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    if current_version > latest_version {
+        return Err(AppError::UnsupportedSchemaVersion { found: current_version, supported: latest_version });
+    }
+
+    if current_version == latest_version {
+        return Ok(());
+    }
+
+    // Bancos anteriores ao controle de versão têm tabelas, mas user_version = 0
+    let has_data: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'tasks'",
+        [],
+        |row| row.get(0),
+    )?;
+
+    if has_data {
+        backup_database(conn, current_version)?;
+    }
+
+    for (index, (description, migration)) in SCHEMA_MIGRATIONS.iter().enumerate().skip(current_version as usize) {
+        let version = index as i64 + 1;
+        let tx = conn.transaction()?;
+
+        migration(&tx)
+            .and_then(|_| tx.pragma_update(None, "user_version", version))
+            .map_err(|e| AppError::Migration { version, description: description.to_string(), message: e.to_string() })?;
+
+        tx.commit()?;
+        log::info!("Migração {} aplicada: {}", version, description);
+    }
+
+    Ok(())
+}
+
+fn backup_database(conn: &Connection, current_version: i64) -> Result<(), AppError> {
+    let path = match conn.path() {
+        Some(path) if !path.is_empty() => path.to_string(),
+        _ => return Ok(()), // banco em memória
+    };
+
+    let backup_path = format!(
+        "{}.backup-v{}-{}",
+        path,
+        current_version,
+        Utc::now().format("%Y%m%d%H%M%S")
+    );
+
+    conn.execute("VACUUM INTO ?1", [&backup_path])
+        .map_err(|e| AppError::Database(format!("Falha ao criar backup antes da migração: {}", e)))?;
+
+    log::info!("Backup do banco criado em {}", backup_path);
+    Ok(())
+}
+
+fn migration_001_initial_schema(conn: &Connection) -> SqliteResult<()> {
+    // IF NOT EXISTS: bancos antigos já têm estas tabelas, só sem user_version
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tasks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            user TEXT NOT NULL,
+            estimated_hours REAL NOT NULL,
+            scheduled_date TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            created_at TEXT NOT NULL,
+            started_at TEXT,
+            completed_at TEXT
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS task_time_logs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            ended_at TEXT NULL,
+            FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pomodoro_sessions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            session_number INTEGER NOT NULL,
+            session_type TEXT NOT NULL CHECK (session_type IN ('work', 'break')),
+            duration_seconds INTEGER NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS active_sessions (
+            task_id INTEGER PRIMARY KEY,
+            pomodoro_id INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
+            FOREIGN KEY (pomodoro_id) REFERENCES pomodoro_sessions (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn migration_002_session_state(conn: &Connection) -> SqliteResult<()> {
+    add_column_if_missing(
+        conn,
+        "pomodoro_sessions",
+        "status",
+        "TEXT NOT NULL DEFAULT 'pending'
+            CHECK (status IN ('pending', 'running', 'completed', 'skipped', 'interrupted'))",
+    )?;
+    add_column_if_missing(conn, "pomodoro_sessions", "started_at", "TEXT NULL")?;
+    add_column_if_missing(conn, "pomodoro_sessions", "ended_at", "TEXT NULL")?;
+    add_column_if_missing(conn, "pomodoro_sessions", "elapsed_seconds", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Sessões que já estavam em andamento antes do rastreamento de estado
+    conn.execute(
+        "UPDATE pomodoro_sessions SET status = 'running', started_at = COALESCE(started_at, (
+             SELECT a.started_at FROM active_sessions a WHERE a.pomodoro_id = pomodoro_sessions.id
+         ))
+         WHERE status = 'pending' AND id IN (SELECT pomodoro_id FROM active_sessions)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn migration_003_pomodoro_profiles(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pomodoro_profiles (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            work_seconds INTEGER NOT NULL CHECK (work_seconds > 0),
+            short_break_seconds INTEGER NOT NULL CHECK (short_break_seconds >= 0),
+            long_break_seconds INTEGER NOT NULL CHECK (long_break_seconds >= 0),
+            rounds_before_long_break INTEGER NOT NULL CHECK (rounds_before_long_break > 0),
+            total_rounds INTEGER NOT NULL CHECK (total_rounds > 0),
+            is_default INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Perfis padrão na primeira execução
+    let profile_count: i64 = conn.query_row("SELECT COUNT(*) FROM pomodoro_profiles", [], |row| row.get(0))?;
+    if profile_count == 0 {
+        let now = Utc::now().to_rfc3339();
+        let defaults = [
+            ("Classic 25/5", 25 * 60, 5 * 60, 15 * 60, 4, 4, true),
+            ("Deep 50/10", 50 * 60, 10 * 60, 30 * 60, 2, 4, false),
+            ("Sprint 15/3", 15 * 60, 3 * 60, 10 * 60, 4, 4, false),
+        ];
+
+        for (name, work, short_break, long_break, rounds_before_long, total_rounds, is_default) in defaults {
+            conn.execute(
+                "INSERT INTO pomodoro_profiles
+                    (name, work_seconds, short_break_seconds, long_break_seconds,
+                     rounds_before_long_break, total_rounds, is_default, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![name, work, short_break, long_break, rounds_before_long, total_rounds, is_default, now],
+            )?;
+        }
+    }
+
+    add_column_if_missing(conn, "tasks", "profile_id", "INTEGER NULL REFERENCES pomodoro_profiles (id) ON DELETE SET NULL")?;
+
+    Ok(())
+}
+
+fn migration_004_session_history(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pomodoro_session_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            pomodoro_id INTEGER NOT NULL,
+            action TEXT NOT NULL CHECK (action IN ('skip', 'extend', 'restart')),
+            amount_seconds INTEGER NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
+            FOREIGN KEY (pomodoro_id) REFERENCES pomodoro_sessions (id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn migration_005_app_settings(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn migration_006_query_indexes(conn: &Connection) -> SqliteResult<()> {
+    // Sem eles, cada consulta por tarefa varre o histórico inteiro de logs e sessões
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_task_time_logs_task_id ON task_time_logs (task_id, started_at);
+         CREATE INDEX IF NOT EXISTS idx_pomodoro_sessions_task_id ON pomodoro_sessions (task_id, session_number);
+         CREATE INDEX IF NOT EXISTS idx_pomodoro_session_history_task_id ON pomodoro_session_history (task_id);
+         CREATE INDEX IF NOT EXISTS idx_tasks_scheduled_date ON tasks (scheduled_date);
+         CREATE INDEX IF NOT EXISTS idx_active_sessions_pomodoro_id ON active_sessions (pomodoro_id);"
+    )
+}
+
+fn migration_007_time_log_heartbeat(conn: &Connection) -> SqliteResult<()> {
+    // Último instante em que o app confirmou que o log aberto ainda estava sendo cronometrado
+    add_column_if_missing(conn, "task_time_logs", "heartbeat_at", "TEXT NULL")
+}
+
+fn migration_008_session_extensions(conn: &Connection) -> SqliteResult<()> {
+    // Parte de duration_seconds que veio de extensões: vale para o timer, mas não é trabalho planejado
+    add_column_if_missing(conn, "pomodoro_sessions", "extended_seconds", "INTEGER NOT NULL DEFAULT 0")?;
+
+    conn.execute(
+        "UPDATE pomodoro_sessions SET extended_seconds = (
+             SELECT COALESCE(SUM(h.amount_seconds), 0) FROM pomodoro_session_history h
+             WHERE h.pomodoro_id = pomodoro_sessions.id AND h.action = 'extend'
+         )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> SqliteResult<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+
+    Ok(())
+}
+
+// Savepoints (ao contrário de BEGIN) funcionam também dentro de uma transação já aberta
+pub(crate) fn with_savepoint<T, E: From<rusqlite::Error>>(conn: &Connection, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    conn.execute_batch("SAVEPOINT clockwise_step")?;
+
+    match f() {
+        Ok(value) => {
+            conn.execute_batch("RELEASE clockwise_step")?;
+            Ok(value)
+        }
+        Err(e) => {
+            conn.execute_batch("ROLLBACK TO clockwise_step; RELEASE clockwise_step")?;
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::status::{InvalidTransition, TaskStatus};
+
+// Erro devolvido pelos comandos: o frontend recebe { code, message, details }
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    TaskNotFound { task_id: i64 },
+    TaskAlreadyActive { task_id: i64 },
+    AnotherTaskActive { task_id: i64, active_task_id: i64 },
+    TaskNotPaused { task_id: i64, status: TaskStatus },
+    InvalidTransition { from: TaskStatus, to: TaskStatus },
+    NoActiveSession { task_id: i64 },
+    SessionAlreadyActive { task_id: i64 },
+    ProfileNotFound { profile_id: i64 },
+    DefaultProfileLocked { profile_id: i64 },
+    TimeLogNotFound { log_id: i64 },
+    TimeLogInProgress { log_id: i64 },
+    TimeLogOverlap { task_id: i64, conflicting_log_id: i64, conflicting_task_id: i64 },
+    Validation { field: &'static str, message: String },
+    UnsupportedSchemaVersion { found: i64, supported: i64 },
+    Migration { version: i64, description: String, message: String },
+    Database(String),
+    Window(String),
+    System(String),
+}
+
+impl AppError {
+    pub fn validation(field: &'static str, message: impl Into<String>) -> AppError {
+        AppError::Validation { field, message: message.into() }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::TaskNotFound { .. } => "TaskNotFound",
+            AppError::TaskAlreadyActive { .. } => "TaskAlreadyActive",
+            AppError::AnotherTaskActive { .. } => "AnotherTaskActive",
+            AppError::TaskNotPaused { .. } => "TaskNotPaused",
+            AppError::InvalidTransition { .. } => "InvalidTransition",
+            AppError::NoActiveSession { .. } => "NoActiveSession",
+            AppError::SessionAlreadyActive { .. } => "SessionAlreadyActive",
+            AppError::ProfileNotFound { .. } => "ProfileNotFound",
+            AppError::DefaultProfileLocked { .. } => "DefaultProfileLocked",
+            AppError::TimeLogNotFound { .. } => "TimeLogNotFound",
+            AppError::TimeLogInProgress { .. } => "TimeLogInProgress",
+            AppError::TimeLogOverlap { .. } => "TimeLogOverlap",
+            AppError::Validation { .. } => "Validation",
+            AppError::UnsupportedSchemaVersion { .. } => "UnsupportedSchemaVersion",
+            AppError::Migration { .. } => "Migration",
+            AppError::Database(_) => "Database",
+            AppError::Window(_) => "Window",
+            AppError::System(_) => "System",
+        }
+    }
+
+    pub fn details(&self) -> serde_json::Value {
+        match self {
+            AppError::TaskNotFound { task_id }
+            | AppError::TaskAlreadyActive { task_id }
+            | AppError::NoActiveSession { task_id }
+            | AppError::SessionAlreadyActive { task_id } => serde_json::json!({ "task_id": task_id }),
+            AppError::AnotherTaskActive { task_id, active_task_id } => {
+                serde_json::json!({ "task_id": task_id, "active_task_id": active_task_id })
+            }
+            AppError::TaskNotPaused { task_id, status } => serde_json::json!({ "task_id": task_id, "status": status }),
+            AppError::InvalidTransition { from, to } => serde_json::json!({ "from": from, "to": to }),
+            AppError::ProfileNotFound { profile_id } | AppError::DefaultProfileLocked { profile_id } => {
+                serde_json::json!({ "profile_id": profile_id })
+            }
+            AppError::TimeLogNotFound { log_id } | AppError::TimeLogInProgress { log_id } => {
+                serde_json::json!({ "log_id": log_id })
+            }
+            AppError::TimeLogOverlap { task_id, conflicting_log_id, conflicting_task_id } => {
+                serde_json::json!({ "task_id": task_id, "conflicting_log_id": conflicting_log_id, "conflicting_task_id": conflicting_task_id })
+            }
+            AppError::Validation { field, .. } => serde_json::json!({ "field": field }),
+            AppError::UnsupportedSchemaVersion { found, supported } => {
+                serde_json::json!({ "found": found, "supported": supported })
+            }
+            AppError::Migration { version, .. } => serde_json::json!({ "version": version }),
+            AppError::Database(_) | AppError::Window(_) | AppError::System(_) => serde_json::Value::Null,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::TaskNotFound { .. } => write!(f, "Tarefa não encontrada"),
+            AppError::TaskAlreadyActive { .. } => write!(f, "Tarefa já está ativa"),
+            AppError::AnotherTaskActive { .. } => {
+                write!(f, "Apenas uma tarefa pode estar em andamento por vez. Pause a tarefa atual primeiro.")
+            }
+            AppError::TaskNotPaused { .. } => write!(f, "Tarefa não está pausada"),
+            AppError::InvalidTransition { from, to } => InvalidTransition { from: *from, to: *to }.fmt(f),
+            AppError::NoActiveSession { .. } => write!(f, "Nenhuma sessão Pomodoro ativa encontrada"),
+            AppError::SessionAlreadyActive { .. } => write!(f, "Tarefa já tem uma sessão Pomodoro ativa"),
+            AppError::ProfileNotFound { .. } => write!(f, "Perfil Pomodoro não encontrado"),
+            AppError::DefaultProfileLocked { .. } => write!(f, "O perfil padrão não pode ser removido"),
+            AppError::TimeLogNotFound { .. } => write!(f, "Registro de tempo não encontrado"),
+            AppError::TimeLogInProgress { .. } => {
+                write!(f, "O registro de tempo ainda está em andamento. Pause a tarefa antes de editá-lo.")
+            }
+            AppError::TimeLogOverlap { task_id, conflicting_task_id, .. } if task_id == conflicting_task_id => {
+                write!(f, "O período se sobrepõe a outro registro de tempo da tarefa")
+            }
+            AppError::TimeLogOverlap { conflicting_task_id, .. } => {
+                write!(f, "O período se sobrepõe a um registro de tempo da tarefa {}", conflicting_task_id)
+            }
+            AppError::Validation { message, .. } => write!(f, "{}", message),
+            AppError::UnsupportedSchemaVersion { found, supported } => write!(
+                f,
+                "O banco de dados está na versão {} do esquema, mais nova que a suportada ({}). Atualize o ClockWise.",
+                found, supported
+            ),
+            AppError::Migration { version, description, message } => {
+                write!(f, "Falha na migração {} ({}): {}", version, description, message)
+            }
+            AppError::Database(message) => write!(f, "Erro no banco de dados: {}", message),
+            AppError::Window(message) => write!(f, "Erro na janela: {}", message),
+            AppError::System(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> AppError {
+        AppError::Database(error.to_string())
+    }
+}
+
+impl From<InvalidTransition> for AppError {
+    fn from(error: InvalidTransition) -> AppError {
+        AppError::InvalidTransition { from: error.from, to: error.to }
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(error: std::sync::PoisonError<T>) -> AppError {
+        AppError::Database(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> AppError {
+        AppError::System(error.to_string())
+    }
+}
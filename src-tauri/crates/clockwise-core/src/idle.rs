@@ -0,0 +1,396 @@
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Connection;
+use x11rb::connection::{Connection as _, RequestConnection as _};
+use x11rb::protocol::screensaver::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::Window;
+use x11rb::rust_connection::RustConnection;
+
+use crate::clock::Clock;
+use crate::error::AppError;
+use crate::models::IdlePeriod;
+use crate::settings::{delete_setting, get_setting, set_setting};
+use crate::tasks::{find_running_task_segment, pause_task_at};
+use crate::time_logs::add_time_log;
+
+// Período pausado por inatividade aguardando a resposta do usuário; só o backend escreve aqui
+const PENDING_IDLE_PERIOD_KEY: &str = "pending_idle_period";
+
+// Fonte do tempo sem entrada do usuário; trocável para testes e para outros ambientes gráficos
+pub trait IdleSource: Send {
+    // None quando a fonte não consegue dizer (ex.: sessão sem ambiente gráfico).
+    // now vem do relógio de quem consulta, para fontes que informam o início da inatividade
+    fn idle_time(&mut self, now: DateTime<Utc>) -> Result<Option<Duration>, AppError>;
+}
+
+fn system_error(error: impl std::fmt::Display) -> AppError {
+    AppError::System(error.to_string())
+}
+
+// Extensão MIT-SCREEN-SAVER do X11 (tempo desde a última entrada, em milissegundos)
+pub struct XScreenSaverIdle {
+    conn: RustConnection,
+    root: Window,
+}
+
+impl XScreenSaverIdle {
+    // None quando não há servidor X ou ele não tem a extensão
+    pub fn connect() -> Result<Option<XScreenSaverIdle>, AppError> {
+        let Ok((conn, screen)) = x11rb::connect(None) else {
+            return Ok(None);
+        };
+        if conn.extension_information(screensaver::X11_EXTENSION_NAME).map_err(system_error)?.is_none() {
+            return Ok(None);
+        }
+
+        let root = conn.setup().roots[screen].root;
+        Ok(Some(XScreenSaverIdle { conn, root }))
+    }
+}
+
+impl IdleSource for XScreenSaverIdle {
+    fn idle_time(&mut self, _now: DateTime<Utc>) -> Result<Option<Duration>, AppError> {
+        let info = self.conn.screensaver_query_info(self.root).map_err(system_error)?
+            .reply().map_err(system_error)?;
+        Ok(Some(Duration::milliseconds(info.ms_since_user_input as i64)))
+    }
+}
+
+// IdleHint do logind: marcado pelo ambiente gráfico, só depois do tempo de inatividade dele
+pub struct LogindIdle {
+    session: zbus::blocking::Proxy<'static>,
+}
+
+impl LogindIdle {
+    // "auto" é a sessão de quem chama
+    pub fn connect(session: &str) -> Result<LogindIdle, AppError> {
+        let conn = zbus::blocking::Connection::system().map_err(system_error)?;
+        let path = format!("/org/freedesktop/login1/session/{}", escape_object_path(session));
+        let session = zbus::blocking::Proxy::new(&conn, "org.freedesktop.login1", path, "org.freedesktop.login1.Session")
+            .map_err(system_error)?;
+        Ok(LogindIdle { session })
+    }
+}
+
+impl IdleSource for LogindIdle {
+    fn idle_time(&mut self, now: DateTime<Utc>) -> Result<Option<Duration>, AppError> {
+        let idle_hint: bool = self.session.get_property("IdleHint").map_err(system_error)?;
+        let idle_since: u64 = self.session.get_property("IdleSinceHint").map_err(system_error)?;
+        Ok(logind_idle_time(idle_hint, idle_since, now))
+    }
+}
+
+// Caminhos de objeto do logind escapam o que não é alfanumérico como _XX (ex.: sessão "2" vira "_32")
+fn escape_object_path(value: &str) -> String {
+    value.bytes().enumerate()
+        .map(|(i, byte)| {
+            if byte.is_ascii_alphabetic() || (byte.is_ascii_digit() && i > 0) {
+                (byte as char).to_string()
+            } else {
+                format!("_{:02x}", byte)
+            }
+        })
+        .collect()
+}
+
+// IdleSinceHint: microssegundos desde a época
+fn logind_idle_time(idle_hint: bool, idle_since_micros: u64, now: DateTime<Utc>) -> Option<Duration> {
+    if !idle_hint {
+        return Some(Duration::zero());
+    }
+
+    let since = DateTime::from_timestamp_micros(i64::try_from(idle_since_micros).ok()?)?;
+    (idle_since_micros > 0).then(|| (now - since).max(Duration::zero()))
+}
+
+// X11 quando há servidor com a extensão; senão logind (Wayland, por exemplo).
+// Sem nenhuma das duas a detecção fica desligada e quem chama avisa no log
+pub fn detect_idle_source(now: DateTime<Utc>) -> Option<Box<dyn IdleSource>> {
+    match XScreenSaverIdle::connect() {
+        Ok(Some(x11)) => {
+            log::info!(target: "idle", "Inatividade lida pela extensão MIT-SCREEN-SAVER do X11");
+            return Some(Box::new(x11));
+        }
+        Ok(_) => log::debug!(target: "idle", "X11 sem a extensão MIT-SCREEN-SAVER ou sem servidor"),
+        Err(e) => log::debug!(target: "idle", "X11 indisponível para inatividade: {}", e),
+    }
+
+    let session = std::env::var("XDG_SESSION_ID")
+        .ok()
+        .filter(|session| !session.is_empty())
+        .unwrap_or_else(|| "auto".to_string());
+    match LogindIdle::connect(&session) {
+        Ok(mut logind) => match logind.idle_time(now) {
+            Ok(Some(_)) => {
+                log::info!(target: "idle", "Inatividade lida pelo IdleHint do logind (sessão {})", session);
+                return Some(Box::new(logind));
+            }
+            Ok(None) => log::debug!(target: "idle", "logind não informa o início da inatividade"),
+            Err(e) => log::debug!(target: "idle", "IdleHint do logind indisponível: {}", e),
+        },
+        Err(e) => log::debug!(target: "idle", "Barramento do sistema indisponível: {}", e),
+    }
+
+    None
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdleAction {
+    // Passou do limite: pausar a tarefa em andamento a partir de idle_since
+    Pause { idle_since: DateTime<Utc> },
+    // O usuário voltou depois de uma pausa por inatividade: perguntar se o tempo conta
+    Returned(IdlePeriod),
+}
+
+// Só decide; quem chama aplica a pausa no banco e avisa com paused() se alguma tarefa foi pausada
+pub struct IdleMonitor {
+    source: Box<dyn IdleSource>,
+    idle_handled: bool,
+    pending: Option<IdlePeriod>,
+}
+
+impl IdleMonitor {
+    pub fn new(source: Box<dyn IdleSource>) -> IdleMonitor {
+        IdleMonitor { source, idle_handled: false, pending: None }
+    }
+
+    pub fn poll(&mut self, now: DateTime<Utc>, threshold: Duration) -> Result<Option<IdleAction>, AppError> {
+        let idle = self.source.idle_time(now)?.unwrap_or_else(Duration::zero);
+
+        if idle >= threshold {
+            // Uma ação por período de inatividade, não uma a cada leitura
+            if self.idle_handled {
+                return Ok(None);
+            }
+            self.idle_handled = true;
+            return Ok(Some(IdleAction::Pause { idle_since: now - idle }));
+        }
+
+        self.idle_handled = false;
+        Ok(self.pending.take().map(|mut period| {
+            period.ended_at = Some((now - idle).to_rfc3339());
+            IdleAction::Returned(period)
+        }))
+    }
+
+    pub fn paused(&mut self, period: IdlePeriod) {
+        self.pending = Some(period);
+    }
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, rusqlite::Error> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| rusqlite::Error::InvalidColumnType(0, "started_at".to_string(), rusqlite::types::Type::Text))
+}
+
+// Pausa pelo mesmo caminho de pause_task e tira dos logs o tempo desde que a atividade parou.
+// O Pomodoro não volta no tempo: se uma sessão começou durante a inatividade, a pausa vale a partir dela.
+pub fn pause_idle_task(conn: &Connection, idle_since: DateTime<Utc>) -> Result<Option<IdlePeriod>, AppError> {
+    let Some((task_id, segment_started_at)) = find_running_task_segment(conn)? else {
+        return Ok(None);
+    };
+
+    let paused_at = idle_since.max(parse_timestamp(&segment_started_at)?);
+    pause_task_at(conn, task_id, &paused_at.to_rfc3339())?;
+
+    // Logs fechados ou abertos durante a inatividade não contam (o usuário decide depois)
+    let idle_since_str = idle_since.to_rfc3339();
+    let mut stmt = conn.prepare(
+        "SELECT id, started_at, ended_at FROM task_time_logs WHERE task_id = ?1 AND ended_at IS NOT NULL"
+    )?;
+    let logs = stmt.query_map([task_id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (log_id, started_at, ended_at) in logs {
+        if parse_timestamp(&started_at)? >= idle_since {
+            conn.execute("DELETE FROM task_time_logs WHERE id = ?1", [log_id])?;
+        } else if parse_timestamp(&ended_at)? > idle_since {
+            conn.execute(
+                "UPDATE task_time_logs SET ended_at = ?1 WHERE id = ?2",
+                rusqlite::params![idle_since_str, log_id],
+            )?;
+        }
+    }
+
+    log::info!("Tarefa {} pausada por inatividade desde {}", task_id, idle_since_str);
+
+    let period = IdlePeriod { task_id, started_at: idle_since_str, ended_at: None };
+    store_pending_idle_period(conn, &period)?;
+    Ok(Some(period))
+}
+
+fn store_pending_idle_period(conn: &Connection, period: &IdlePeriod) -> Result<(), AppError> {
+    let value = serde_json::to_string(period).map_err(system_error)?;
+    set_setting(conn, PENDING_IDLE_PERIOD_KEY, &value)?;
+    Ok(())
+}
+
+pub fn load_pending_idle_period(conn: &Connection) -> Result<Option<IdlePeriod>, AppError> {
+    get_setting(conn, PENDING_IDLE_PERIOD_KEY)?
+        .map(|value| serde_json::from_str(&value).map_err(system_error))
+        .transpose()
+}
+
+// O usuário voltou: fecha o período guardado no horário medido pelo monitor
+pub fn record_idle_return(conn: &Connection, ended_at: DateTime<Utc>) -> Result<Option<IdlePeriod>, AppError> {
+    let Some(mut period) = load_pending_idle_period(conn)? else {
+        return Ok(None);
+    };
+
+    period.ended_at = Some(ended_at.to_rfc3339());
+    store_pending_idle_period(conn, &period)?;
+    Ok(Some(period))
+}
+
+// Resposta do usuário ao voltar, sobre o período guardado pelo backend (o frontend só diz se conta).
+// Manter o tempo vira um registro fechado; descartar não muda nada
+pub fn resolve_idle_period(conn: &Connection, clock: &dyn Clock, keep: bool) -> Result<IdlePeriod, AppError> {
+    let period = load_pending_idle_period(conn)?
+        .ok_or_else(|| AppError::validation("keep", "Nenhum período de inatividade aguardando resposta"))?;
+    let ended_at = period.ended_at.as_deref()
+        .ok_or_else(|| AppError::validation("keep", "O período de inatividade ainda não terminou"))?;
+
+    if keep {
+        add_time_log(conn, clock, period.task_id, &period.started_at, ended_at)?;
+    } else {
+        log::info!("Tempo inativo da tarefa {} descartado", period.task_id);
+    }
+
+    delete_setting(conn, PENDING_IDLE_PERIOD_KEY)?;
+    Ok(period)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::clock::FakeClock;
+    use crate::database::init_in_memory_database;
+    use crate::pomodoro::check_and_advance_pomodoro_sessions;
+    use crate::status::TaskStatus;
+    use crate::tasks::{get_task_by_id, start_task};
+    use crate::time_logs::get_task_remaining_time;
+
+    // Inatividade controlada pelo teste, compartilhada com o monitor
+    #[derive(Clone, Default)]
+    struct FakeIdleSource(Arc<Mutex<Duration>>);
+
+    impl FakeIdleSource {
+        fn set(&self, idle: Duration) {
+            *self.0.lock().unwrap() = idle;
+        }
+    }
+
+    impl IdleSource for FakeIdleSource {
+        fn idle_time(&mut self, _now: DateTime<Utc>) -> Result<Option<Duration>, AppError> {
+            Ok(Some(*self.0.lock().unwrap()))
+        }
+    }
+
+    // Tarefa de 1 h iniciada às 09:00 com o perfil clássico (25 min de trabalho)
+    fn setup() -> (Connection, FakeClock, i64) {
+        let conn = init_in_memory_database().unwrap();
+        let clock = FakeClock::new("2026-01-01T09:00:00Z".parse().unwrap());
+        conn.execute(
+            "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at)
+             VALUES ('tarefa', 'teste', 1.0, '2026-01-01', 'pending', '2026-01-01T08:00:00+00:00')",
+            [],
+        ).unwrap();
+        let task_id = conn.last_insert_rowid();
+        start_task(&conn, &clock, task_id, None, None).unwrap();
+        (conn, clock, task_id)
+    }
+
+    fn status(conn: &Connection, task_id: i64) -> TaskStatus {
+        get_task_by_id(conn, task_id).unwrap().unwrap().status
+    }
+
+    #[test]
+    fn idle_pauses_once_and_keeping_the_time_restores_it() {
+        let (conn, clock, task_id) = setup();
+        let source = FakeIdleSource::default();
+        let mut monitor = IdleMonitor::new(Box::new(source.clone()));
+        let threshold = Duration::minutes(5);
+
+        clock.advance(Duration::minutes(10));
+        source.set(Duration::minutes(2));
+        assert_eq!(monitor.poll(clock.now(), threshold).unwrap(), None);
+
+        clock.advance(Duration::minutes(4));
+        source.set(Duration::minutes(6));
+        let idle_since: DateTime<Utc> = "2026-01-01T09:08:00Z".parse().unwrap();
+        assert_eq!(monitor.poll(clock.now(), threshold).unwrap(), Some(IdleAction::Pause { idle_since }));
+
+        let period = pause_idle_task(&conn, idle_since).unwrap().unwrap();
+        monitor.paused(period.clone());
+        assert_eq!(status(&conn, task_id), TaskStatus::Paused);
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 3600 - 8 * 60);
+
+        // Ainda inativo: nada de novo
+        clock.advance(Duration::minutes(1));
+        source.set(Duration::minutes(7));
+        assert_eq!(monitor.poll(clock.now(), threshold).unwrap(), None);
+
+        clock.advance(Duration::minutes(16));
+        source.set(Duration::seconds(10));
+        let returned = match monitor.poll(clock.now(), threshold).unwrap() {
+            Some(IdleAction::Returned(returned)) => returned,
+            other => panic!("esperava a volta do usuário, veio {:?}", other),
+        };
+        assert_eq!(returned.ended_at.as_deref(), Some("2026-01-01T09:30:50+00:00"));
+        assert_eq!(monitor.poll(clock.now(), threshold).unwrap(), None);
+
+        // Antes da volta não há o que resolver
+        assert!(matches!(resolve_idle_period(&conn, &clock, true), Err(AppError::Validation { .. })));
+
+        let recorded = record_idle_return(&conn, "2026-01-01T09:30:50Z".parse().unwrap()).unwrap();
+        assert_eq!(recorded, Some(returned.clone()));
+
+        assert_eq!(resolve_idle_period(&conn, &clock, true).unwrap(), returned);
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 3600 - 8 * 60 - (22 * 60 + 50));
+
+        // Resolvido uma vez só: repetir não cria outro registro
+        assert!(resolve_idle_period(&conn, &clock, true).is_err());
+        assert_eq!(load_pending_idle_period(&conn).unwrap(), None);
+    }
+
+    #[test]
+    fn idle_time_is_removed_even_if_a_session_changed_meanwhile() {
+        let (conn, clock, task_id) = setup();
+
+        // Parou às 09:20; o trabalho terminou às 09:25 sem ninguém ver
+        clock.advance(Duration::minutes(26));
+        check_and_advance_pomodoro_sessions(&conn, &clock).unwrap();
+        assert_eq!(status(&conn, task_id), TaskStatus::Waiting);
+
+        let period = pause_idle_task(&conn, "2026-01-01T09:20:00Z".parse().unwrap()).unwrap().unwrap();
+        assert_eq!(period.started_at, "2026-01-01T09:20:00+00:00");
+        assert_eq!(status(&conn, task_id), TaskStatus::Paused);
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 3600 - 20 * 60);
+
+        // Descartar mantém o período fora; sem tarefa em andamento não há o que pausar
+        assert_eq!(load_pending_idle_period(&conn).unwrap(), Some(period));
+        record_idle_return(&conn, clock.now()).unwrap();
+        resolve_idle_period(&conn, &clock, false).unwrap();
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 3600 - 20 * 60);
+        assert_eq!(load_pending_idle_period(&conn).unwrap(), None);
+        assert_eq!(pause_idle_task(&conn, clock.now()).unwrap(), None);
+    }
+
+    #[test]
+    fn logind_idle_hint_is_measured_with_the_given_clock() {
+        let clock = FakeClock::new("2026-01-01T09:10:00Z".parse().unwrap());
+        let since = "2026-01-01T09:00:00Z".parse::<DateTime<Utc>>().unwrap().timestamp_micros() as u64;
+
+        assert_eq!(logind_idle_time(true, since, clock.now()), Some(Duration::minutes(10)));
+        clock.advance(Duration::minutes(5));
+        assert_eq!(logind_idle_time(true, since, clock.now()), Some(Duration::minutes(15)));
+        assert_eq!(logind_idle_time(false, 0, clock.now()), Some(Duration::zero()));
+        assert_eq!(logind_idle_time(true, 0, clock.now()), None);
+
+        assert_eq!(escape_object_path("auto"), "auto");
+        assert_eq!(escape_object_path("2"), "_32");
+        assert_eq!(escape_object_path("c1"), "c1");
+    }
+}
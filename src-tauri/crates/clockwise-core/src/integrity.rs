@@ -0,0 +1,398 @@
+use std::collections::HashSet;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::status::{SessionStatus, TaskStatus};
+use crate::tasks::pause_task_at;
+use crate::time_logs::close_open_time_logs;
+
+// (id, task_id, started_at, ended_at) de um log com datas válidas
+type ParsedLog = (i64, i64, chrono::DateTime<chrono::FixedOffset>, Option<chrono::DateTime<chrono::FixedOffset>>);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityIssue {
+    pub kind: String, // "orphan", "negative_log", "overlapping_logs", "multiple_open_logs", "dangling_active_session",
+                      // "multiple_running_sessions", "invalid_timestamp"
+    pub table: String,
+    pub row_id: i64,
+    pub task_id: Option<i64>,
+    pub description: String,
+    pub repaired: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+    pub repair_requested: bool,
+}
+
+pub fn check_database_integrity(conn: &Connection, repair: bool) -> Result<IntegrityReport, AppError> {
+    let tx = conn.unchecked_transaction()?;
+    let mut issues = Vec::new();
+
+    // active_sessions apontando para sessões Pomodoro que não existem mais
+    let dangling: Vec<(i64, i64)> = tx.prepare(
+        "SELECT a.task_id, a.pomodoro_id FROM active_sessions a
+         LEFT JOIN pomodoro_sessions p ON a.pomodoro_id = p.id
+         WHERE p.id IS NULL"
+    )?.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let dangling_task_ids: HashSet<i64> = dangling.iter().map(|(task_id, _)| *task_id).collect();
+    for (task_id, pomodoro_id) in dangling {
+        if repair {
+            repair_dangling_active_session(&tx, task_id)?;
+        }
+        issues.push(IntegrityIssue {
+            kind: "dangling_active_session".to_string(),
+            table: "active_sessions".to_string(),
+            row_id: task_id,
+            task_id: Some(task_id),
+            description: format!("Sessão ativa aponta para a sessão Pomodoro {} inexistente", pomodoro_id),
+            repaired: repair,
+        });
+    }
+
+    // Linhas órfãs de qualquer tabela com chave estrangeira
+    let orphans: Vec<(String, i64, String)> = tx.prepare("PRAGMA foreign_key_check")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (table, row_id, parent) in orphans {
+        // Sessão ativa pendurada já foi relatada acima (a chave de active_sessions é o task_id)
+        if table == "active_sessions" && parent == "pomodoro_sessions" && dangling_task_ids.contains(&row_id) {
+            continue;
+        }
+
+        if repair {
+            if table == "tasks" {
+                // Única referência de tasks é o perfil (ON DELETE SET NULL)
+                tx.execute("UPDATE tasks SET profile_id = NULL WHERE rowid = ?1", [row_id])?;
+            } else {
+                tx.execute(&format!("DELETE FROM {} WHERE rowid = ?1", table), [row_id])?;
+            }
+        }
+        issues.push(IntegrityIssue {
+            kind: "orphan".to_string(),
+            table: table.clone(),
+            row_id,
+            task_id: None,
+            description: format!("Linha {} de {} referencia um registro inexistente em {}", row_id, table, parent),
+            repaired: repair,
+        });
+    }
+
+    // Mais de uma sessão Pomodoro em andamento na mesma tarefa: fica a apontada por active_sessions
+    // (ou a mais recente) e as outras viram interrompidas, sem tempo além do já registrado
+    // A primeira de cada tarefa na ordenação é a que fica
+    let running: Vec<(i64, i64)> = tx.prepare(
+        "SELECT p.task_id, p.id FROM pomodoro_sessions p
+         LEFT JOIN active_sessions a ON a.task_id = p.task_id
+         WHERE p.status = ?1
+           AND p.task_id IN (SELECT task_id FROM pomodoro_sessions WHERE status = ?1
+                             GROUP BY task_id HAVING COUNT(*) > 1)
+         ORDER BY p.task_id, (p.id = a.pomodoro_id) DESC, p.session_number DESC"
+    )?.query_map([SessionStatus::Running], |row| Ok((row.get(0)?, row.get(1)?)))?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let mut previous_task_id = None;
+    for (task_id, session_id) in running {
+        if previous_task_id.replace(task_id) != Some(task_id) {
+            continue;
+        }
+
+        if repair {
+            tx.execute(
+                "UPDATE pomodoro_sessions SET status = ?1, ended_at = COALESCE(ended_at, started_at)
+                 WHERE id = ?2",
+                rusqlite::params![SessionStatus::Interrupted, session_id],
+            )?;
+        }
+        issues.push(IntegrityIssue {
+            kind: "multiple_running_sessions".to_string(),
+            table: "pomodoro_sessions".to_string(),
+            row_id: session_id,
+            task_id: Some(task_id),
+            description: format!("Sessão Pomodoro {} está em andamento junto com outra da tarefa {}", session_id, task_id),
+            repaired: repair,
+        });
+    }
+
+    // Logs de tempo: durações negativas, sobreposições e múltiplos logs abertos por tarefa
+    let logs: Vec<(i64, i64, String, Option<String>)> = tx.prepare(
+        "SELECT id, task_id, started_at, ended_at FROM task_time_logs ORDER BY task_id, started_at, id"
+    )?.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let mut parsed_logs = Vec::new();
+    for (id, task_id, started_at_str, ended_at_str) in logs {
+        let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str);
+        let ended_at = ended_at_str.as_deref().map(chrono::DateTime::parse_from_rfc3339).transpose();
+
+        match (started_at, ended_at) {
+            (Ok(started_at), Ok(ended_at)) => parsed_logs.push((id, task_id, started_at, ended_at)),
+            _ => issues.push(IntegrityIssue {
+                kind: "invalid_timestamp".to_string(),
+                table: "task_time_logs".to_string(),
+                row_id: id,
+                task_id: Some(task_id),
+                description: format!("Log {} tem datas que não estão em RFC3339", id),
+                repaired: false,
+            }),
+        }
+    }
+
+    let mut previous: Option<ParsedLog> = None;
+    for (id, task_id, started_at, ended_at) in parsed_logs {
+        if let Some(ended) = ended_at {
+            if ended < started_at {
+                if repair {
+                    tx.execute("DELETE FROM task_time_logs WHERE id = ?1", [id])?;
+                }
+                issues.push(IntegrityIssue {
+                    kind: "negative_log".to_string(),
+                    table: "task_time_logs".to_string(),
+                    row_id: id,
+                    task_id: Some(task_id),
+                    description: format!("Log {} termina antes de começar", id),
+                    repaired: repair,
+                });
+                continue;
+            }
+        }
+
+        if let Some((prev_id, prev_task_id, _, prev_ended_at)) = previous {
+            if prev_task_id == task_id {
+                match prev_ended_at {
+                    // Log anterior ficou aberto enquanto outro começou: fechá-lo no início do seguinte
+                    None => {
+                        if repair {
+                            tx.execute(
+                                "UPDATE task_time_logs SET ended_at = ?1 WHERE id = ?2",
+                                rusqlite::params![started_at.to_rfc3339(), prev_id],
+                            )?;
+                        }
+                        issues.push(IntegrityIssue {
+                            kind: "multiple_open_logs".to_string(),
+                            table: "task_time_logs".to_string(),
+                            row_id: prev_id,
+                            task_id: Some(task_id),
+                            description: format!("Log {} continua aberto, mas o log {} começou depois", prev_id, id),
+                            repaired: repair,
+                        });
+                    }
+                    // Sobreposição: o log seguinte passa a começar quando o anterior termina
+                    Some(prev_ended) if prev_ended > started_at => {
+                        let swallowed = ended_at.map(|ended| ended <= prev_ended).unwrap_or(false);
+                        if repair {
+                            if swallowed {
+                                tx.execute("DELETE FROM task_time_logs WHERE id = ?1", [id])?;
+                            } else {
+                                tx.execute(
+                                    "UPDATE task_time_logs SET started_at = ?1 WHERE id = ?2",
+                                    rusqlite::params![prev_ended.to_rfc3339(), id],
+                                )?;
+                            }
+                        }
+                        issues.push(IntegrityIssue {
+                            kind: "overlapping_logs".to_string(),
+                            table: "task_time_logs".to_string(),
+                            row_id: id,
+                            task_id: Some(task_id),
+                            description: format!("Log {} se sobrepõe ao log {}", id, prev_id),
+                            repaired: repair,
+                        });
+
+                        if swallowed {
+                            continue;
+                        }
+                        previous = Some((id, task_id, prev_ended, ended_at));
+                        continue;
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        previous = Some((id, task_id, started_at, ended_at));
+    }
+
+    if repair {
+        tx.commit()?;
+    }
+
+    Ok(IntegrityReport {
+        issues,
+        repair_requested: repair,
+    })
+}
+
+// Fecha a tarefa no último instante em que se sabe que ela rodava: o batimento do log aberto
+// ou o início do trecho da sessão ativa, nunca o momento da verificação
+fn repair_dangling_active_session(conn: &Connection, task_id: i64) -> Result<(), AppError> {
+    let (status, stopped_at): (TaskStatus, String) = conn.query_row(
+        "SELECT t.status, COALESCE(MAX(COALESCE(l.heartbeat_at, l.started_at)), a.started_at)
+         FROM active_sessions a
+         JOIN tasks t ON t.id = a.task_id
+         LEFT JOIN task_time_logs l ON l.task_id = a.task_id AND l.ended_at IS NULL
+         WHERE a.task_id = ?1
+         GROUP BY a.task_id",
+        [task_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    // Tarefa rodando: mesmo caminho de pause_task (fecha o log, remove a sessão, passa a pausada)
+    if status.is_running() {
+        return pause_task_at(conn, task_id, &stopped_at);
+    }
+
+    close_open_time_logs(conn, task_id, &stopped_at)?;
+    conn.execute("DELETE FROM active_sessions WHERE task_id = ?1", [task_id])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+    use crate::database::init_in_memory_database;
+    use crate::tasks::{add_task, get_task_by_id, start_task};
+
+    fn kinds(report: &IntegrityReport) -> Vec<&str> {
+        report.issues.iter().map(|issue| issue.kind.as_str()).collect()
+    }
+
+    fn insert_task(conn: &Connection) -> i64 {
+        let clock = FakeClock::new("2026-01-01T08:00:00Z".parse().unwrap());
+        add_task(conn, &clock, "tarefa".to_string(), "teste".to_string(), 1.0, "2026-01-01".to_string(), None)
+            .unwrap().id.unwrap()
+    }
+
+    fn insert_log(conn: &Connection, task_id: i64, started_at: &str, ended_at: Option<&str>) {
+        conn.execute(
+            "INSERT INTO task_time_logs (task_id, started_at, ended_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![task_id, started_at, ended_at],
+        ).unwrap();
+    }
+
+    fn logs(conn: &Connection, task_id: i64) -> Vec<(String, Option<String>)> {
+        conn.prepare("SELECT started_at, ended_at FROM task_time_logs WHERE task_id = ?1 ORDER BY started_at").unwrap()
+            .query_map([task_id], |row| Ok((row.get(0)?, row.get(1)?))).unwrap()
+            .collect::<Result<_, _>>().unwrap()
+    }
+
+    fn running_task() -> (Connection, i64) {
+        let conn = init_in_memory_database().unwrap();
+        let clock = FakeClock::new("2026-01-01T12:00:00Z".parse().unwrap());
+        let task_id = add_task(&conn, &clock, "tarefa".to_string(), "teste".to_string(), 1.0, "2026-01-01".to_string(), None)
+            .unwrap().id.unwrap();
+        start_task(&conn, &clock, task_id, None, None).unwrap();
+        (conn, task_id)
+    }
+
+    #[test]
+    fn dangling_active_session_is_reported_once() {
+        let (conn, task_id) = running_task();
+        conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+        conn.execute("DELETE FROM pomodoro_sessions WHERE task_id = ?1 AND status = 'running'", [task_id]).unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+
+        assert_eq!(kinds(&check_database_integrity(&conn, false).unwrap()), vec!["dangling_active_session"]);
+
+        // O log aberto fecha no último batimento e a tarefa passa a pausada
+        conn.execute("UPDATE task_time_logs SET heartbeat_at = '2026-01-01T12:20:00+00:00' WHERE task_id = ?1", [task_id]).unwrap();
+        check_database_integrity(&conn, true).unwrap();
+        assert!(check_database_integrity(&conn, false).unwrap().issues.is_empty());
+        assert_eq!(get_task_by_id(&conn, task_id).unwrap().unwrap().status, TaskStatus::Paused);
+        assert_eq!(logs(&conn, task_id), vec![("2026-01-01T12:00:00+00:00".to_string(), Some("2026-01-01T12:20:00+00:00".to_string()))]);
+    }
+
+    #[test]
+    fn orphan_rows_are_removed_or_unlinked_on_repair() {
+        let conn = init_in_memory_database().unwrap();
+        let task_id = insert_task(&conn);
+        conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+        conn.execute(
+            "INSERT INTO task_time_logs (task_id, started_at, ended_at)
+             VALUES (999, '2026-01-01T09:00:00+00:00', '2026-01-01T10:00:00+00:00')",
+            [],
+        ).unwrap();
+        conn.execute("UPDATE tasks SET profile_id = 999 WHERE id = ?1", [task_id]).unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+
+        let report = check_database_integrity(&conn, true).unwrap();
+        assert_eq!(kinds(&report), vec!["orphan", "orphan"]);
+
+        assert!(check_database_integrity(&conn, false).unwrap().issues.is_empty());
+        assert_eq!(logs(&conn, 999), vec![]);
+        assert_eq!(get_task_by_id(&conn, task_id).unwrap().unwrap().profile_id, None);
+    }
+
+    #[test]
+    fn negative_log_is_deleted_on_repair() {
+        let conn = init_in_memory_database().unwrap();
+        let task_id = insert_task(&conn);
+        insert_log(&conn, task_id, "2026-01-01T10:00:00+00:00", Some("2026-01-01T11:00:00+00:00"));
+        insert_log(&conn, task_id, "2026-01-01T13:00:00+00:00", Some("2026-01-01T12:00:00+00:00"));
+
+        assert_eq!(kinds(&check_database_integrity(&conn, true).unwrap()), vec!["negative_log"]);
+        assert_eq!(logs(&conn, task_id), vec![
+            ("2026-01-01T10:00:00+00:00".to_string(), Some("2026-01-01T11:00:00+00:00".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn overlapping_logs_are_trimmed_or_deleted_on_repair() {
+        let conn = init_in_memory_database().unwrap();
+        let task_id = insert_task(&conn);
+        insert_log(&conn, task_id, "2026-01-01T10:00:00+00:00", Some("2026-01-01T11:00:00+00:00"));
+        // Começa antes do fim do anterior: passa a começar às 11:00
+        insert_log(&conn, task_id, "2026-01-01T10:30:00+00:00", Some("2026-01-01T11:30:00+00:00"));
+        // Inteiro dentro do anterior (já ajustado): removido
+        insert_log(&conn, task_id, "2026-01-01T11:10:00+00:00", Some("2026-01-01T11:20:00+00:00"));
+
+        assert_eq!(kinds(&check_database_integrity(&conn, true).unwrap()), vec!["overlapping_logs", "overlapping_logs"]);
+        assert!(check_database_integrity(&conn, false).unwrap().issues.is_empty());
+        assert_eq!(logs(&conn, task_id), vec![
+            ("2026-01-01T10:00:00+00:00".to_string(), Some("2026-01-01T11:00:00+00:00".to_string())),
+            ("2026-01-01T11:00:00+00:00".to_string(), Some("2026-01-01T11:30:00+00:00".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn earlier_open_log_is_closed_when_the_next_one_starts() {
+        let conn = init_in_memory_database().unwrap();
+        let task_id = insert_task(&conn);
+        insert_log(&conn, task_id, "2026-01-01T10:00:00+00:00", None);
+        insert_log(&conn, task_id, "2026-01-01T10:45:00+00:00", None);
+
+        assert_eq!(kinds(&check_database_integrity(&conn, true).unwrap()), vec!["multiple_open_logs"]);
+        assert!(check_database_integrity(&conn, false).unwrap().issues.is_empty());
+        assert_eq!(logs(&conn, task_id), vec![
+            ("2026-01-01T10:00:00+00:00".to_string(), Some("2026-01-01T10:45:00+00:00".to_string())),
+            ("2026-01-01T10:45:00+00:00".to_string(), None),
+        ]);
+    }
+
+    #[test]
+    fn second_running_session_is_interrupted_on_repair() {
+        let (conn, task_id) = running_task();
+        let active_id: i64 = conn.query_row("SELECT pomodoro_id FROM active_sessions WHERE task_id = ?1", [task_id], |row| row.get(0)).unwrap();
+        conn.execute(
+            "UPDATE pomodoro_sessions SET status = 'running', started_at = '2026-01-01T12:30:00+00:00'
+             WHERE task_id = ?1 AND session_number = 3",
+            [task_id],
+        ).unwrap();
+
+        let report = check_database_integrity(&conn, true).unwrap();
+        assert_eq!(kinds(&report), vec!["multiple_running_sessions"]);
+        assert_ne!(report.issues[0].row_id, active_id);
+
+        let running: Vec<i64> = conn.prepare("SELECT id FROM pomodoro_sessions WHERE task_id = ?1 AND status = 'running'").unwrap()
+            .query_map([task_id], |row| row.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(running, vec![active_id]);
+    }
+}
@@ -0,0 +1,27 @@
+//! Regras do ClockWise (tarefas, logs de tempo, motor Pomodoro e relatórios) sem dependência do Tauri.
+//!
+//! Todas as operações recebem uma `&Connection`; quem chama decide a transação.
+//! No app, a conexão fica com um [`DbWorker`] e os comandos enviam pedidos para ele.
+
+pub mod clock;
+pub mod database;
+pub mod error;
+pub mod idle;
+pub mod integrity;
+pub mod models;
+pub mod pomodoro;
+pub mod profiles;
+pub mod recovery;
+pub mod settings;
+pub mod status;
+pub mod system_events;
+pub mod tasks;
+pub mod time_logs;
+pub mod worker;
+
+pub use clock::{Clock, FakeClock, SystemClock};
+pub use error::AppError;
+pub use models::*;
+pub use settings::OfflinePolicy;
+pub use status::{InvalidTransition, SessionStatus, SessionType, TaskStatus};
+pub use worker::DbWorker;
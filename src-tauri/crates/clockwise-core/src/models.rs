@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+
+use crate::status::{SessionStatus, SessionType, TaskStatus};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Task {
+    pub id: Option<i64>,
+    pub name: String,
+    pub user: String,
+    pub estimated_hours: f64,
+    pub scheduled_date: String,
+    pub status: TaskStatus,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub profile_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskTimeLog {
+    pub id: Option<i64>,
+    pub task_id: i64,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroSession {
+    pub id: Option<i64>,
+    pub task_id: i64,
+    pub session_number: i32,
+    pub session_type: SessionType,
+    pub duration_seconds: i32,
+    pub created_at: String,
+    pub status: SessionStatus,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub elapsed_seconds: i32, // tempo já cumprido antes de interrupções
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActiveSession {
+    pub task_id: i64,
+    pub pomodoro_id: i64,
+    pub started_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskWithActiveSession {
+    pub id: Option<i64>,
+    pub name: String,
+    pub user: String,
+    pub estimated_hours: f64,
+    pub scheduled_date: String,
+    pub status: TaskStatus,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub profile_id: Option<i64>,
+    pub active_session: Option<ActiveSessionInfo>,
+    pub pomodoro_sessions: Vec<PomodoroSessionInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroSessionInfo {
+    pub id: Option<i64>,
+    pub session_number: i32,
+    pub session_type: SessionType,
+    pub duration_seconds: i32,
+    pub created_at: String,
+    pub is_active: bool,
+    pub status: SessionStatus,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub elapsed_seconds: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActiveSessionInfo {
+    pub session_type: SessionType,
+    pub started_at: String,
+    pub ends_at: String,
+    pub duration_seconds: i32,
+    pub elapsed_seconds: i32, // cumprido antes da última retomada
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroProfile {
+    pub id: Option<i64>,
+    pub name: String,
+    pub work_seconds: i32,
+    pub short_break_seconds: i32,
+    pub long_break_seconds: i32,
+    pub rounds_before_long_break: i32,
+    pub total_rounds: i32, // usado apenas quando a tarefa não tem estimativa válida
+    pub is_default: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TaskUpdate {
+    pub name: Option<String>,
+    pub user: Option<String>,
+    pub estimated_hours: Option<f64>,
+    pub scheduled_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskChangedPayload {
+    pub task_id: i64,
+    pub remaining_seconds: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimerTickPayload {
+    pub task_id: i64,
+    pub pomodoro_id: i64,
+    pub session_number: i32,
+    pub session_type: SessionType,
+    pub remaining_seconds: i64, // da sessão atual
+    pub ends_at: String,
+    pub task_remaining_seconds: i64, // da estimativa da tarefa; negativo quando passou do estimado
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionTransitionPayload {
+    pub task_id: i64,
+    pub task_status: TaskStatus,
+    pub session_number: Option<i32>,
+    pub session_type: Option<SessionType>,
+}
+
+// Período sem atividade que pausou uma tarefa; ended_at é preenchido quando o usuário volta
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IdlePeriod {
+    pub task_id: i64,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+// Log deixado aberto por um app que caiu, fechado no último batimento
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RecoveredTimeLog {
+    pub log_id: i64,
+    pub task_id: i64,
+    pub task_name: String,
+    pub started_at: String,
+    pub closed_at: String,
+    pub discarded_seconds: i64, // tempo entre o último batimento e a recuperação, que não foi contado
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RecoverySummary {
+    pub recovered_at: String,
+    pub logs: Vec<RecoveredTimeLog>,
+}
+
+// Tempo de uma tarefa num dia do calendário do usuário; date no formato AAAA-MM-DD
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DailyTimeTotal {
+    pub date: String,
+    pub task_id: i64,
+    pub seconds: i64,
+}
@@ -0,0 +1,549 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::clock::Clock;
+use crate::database::with_savepoint;
+use crate::error::AppError;
+use crate::models::{PomodoroProfile, PomodoroSession, SessionTransitionPayload, TimerTickPayload};
+use crate::profiles::get_task_pomodoro_profile;
+use crate::settings::OfflinePolicy;
+use crate::status::{SessionStatus, SessionType, TaskStatus};
+use crate::tasks::set_task_status;
+use crate::time_logs::calculate_task_remaining_time;
+
+pub fn create_pomodoro_cycles(conn: &Connection, task_id: i64, now: &str) -> Result<(), rusqlite::Error> {
+    create_pomodoro_cycles_after(conn, task_id, 0, now)
+}
+
+// Sobra de trabalho menor que isso é incorporada à sessão anterior em vez de virar uma sessão curta
+pub(crate) const MIN_PARTIAL_WORK_SECONDS: i64 = 5 * 60;
+
+pub(crate) struct PlanStart {
+    // Trabalho já coberto por sessões existentes (histórico ou em andamento)
+    pub planned_work_seconds: i64,
+    // Rodadas de trabalho já existentes, para manter a cadência da pausa longa
+    pub rounds_done: i32,
+    // Se a última sessão existente é de trabalho, o plano começa com uma pausa
+    pub last_was_work: bool,
+}
+
+pub(crate) fn split_work_seconds(work_seconds_to_plan: i64, session_seconds: i64) -> Vec<i64> {
+    let mut chunks = Vec::new();
+    let mut remaining = work_seconds_to_plan;
+
+    while remaining > 0 {
+        let chunk = remaining.min(session_seconds);
+        remaining -= chunk;
+        chunks.push(chunk);
+    }
+
+    // Última sessão parcial: encurtada, ou incorporada à anterior se for curta demais
+    if chunks.len() > 1 {
+        let last = *chunks.last().unwrap();
+        if last < MIN_PARTIAL_WORK_SECONDS.min(session_seconds) {
+            chunks.pop();
+            *chunks.last_mut().unwrap() += last;
+        }
+    }
+
+    chunks
+}
+
+pub(crate) fn build_pomodoro_plan(profile: &PomodoroProfile, estimated_hours: f64, start: &PlanStart) -> Vec<(SessionType, i32)> {
+    // Sem estimativa válida, cai para o número fixo de rodadas do perfil
+    let estimated_seconds = if estimated_hours.is_finite() && estimated_hours > 0.0 {
+        (estimated_hours * 3600.0).round() as i64
+    } else {
+        profile.total_rounds as i64 * profile.work_seconds as i64
+    };
+
+    let work_chunks = split_work_seconds(
+        estimated_seconds - start.planned_work_seconds,
+        profile.work_seconds as i64,
+    );
+
+    let break_after = |round: i32| -> i32 {
+        if round % profile.rounds_before_long_break == 0 {
+            profile.long_break_seconds
+        } else {
+            profile.short_break_seconds
+        }
+    };
+
+    let mut plan = Vec::new();
+    let mut round = start.rounds_done;
+
+    if start.last_was_work && !work_chunks.is_empty() && break_after(round) > 0 {
+        plan.push((SessionType::Break, break_after(round)));
+    }
+
+    for (i, chunk) in work_chunks.iter().enumerate() {
+        round += 1;
+        plan.push((SessionType::Work, *chunk as i32));
+
+        // Depois da última sessão só entra a pausa longa, que fecha o ciclo
+        // (trabalho → pausa → ... → pausa longa → fim); uma pausa curta final não teria o que separar
+        let is_last = i + 1 == work_chunks.len();
+        let is_long_break = round % profile.rounds_before_long_break == 0;
+        if (!is_last || is_long_break) && break_after(round) > 0 {
+            plan.push((SessionType::Break, break_after(round)));
+        }
+    }
+
+    plan
+}
+
+pub(crate) fn create_pomodoro_cycles_after(conn: &Connection, task_id: i64, after_session_number: i32, now: &str) -> Result<(), rusqlite::Error> {
+    let profile = get_task_pomodoro_profile(conn, task_id)?;
+    let estimated_hours: f64 = conn.query_row(
+        "SELECT estimated_hours FROM tasks WHERE id = ?1",
+        [task_id],
+        |row| row.get(0),
+    )?;
+
+    // Sessões mantidas (até after_session_number) já cobrem parte da estimativa; extensões ficam de fora
+    let mut stmt = conn.prepare(
+        "SELECT session_type, duration_seconds - extended_seconds, status FROM pomodoro_sessions
+         WHERE task_id = ?1 AND session_number <= ?2
+         ORDER BY session_number ASC"
+    )?;
+    let kept: Vec<(SessionType, i32, SessionStatus)> = stmt.query_map([task_id, after_session_number as i64], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+    })?.collect::<Result<Vec<_>, _>>()?;
+
+    let start = PlanStart {
+        planned_work_seconds: kept.iter()
+            .filter(|(session_type, _, status)| *session_type == SessionType::Work && *status != SessionStatus::Skipped)
+            .map(|(_, duration, _)| *duration as i64)
+            .sum(),
+        rounds_done: kept.iter().filter(|(session_type, _, _)| *session_type == SessionType::Work).count() as i32,
+        last_was_work: kept.last().map(|(session_type, _, _)| *session_type == SessionType::Work).unwrap_or(false),
+    };
+
+    let cycles = build_pomodoro_plan(&profile, estimated_hours, &start);
+
+    for (i, (session_type, duration)) in cycles.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO pomodoro_sessions (task_id, session_number, session_type, duration_seconds, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![task_id, after_session_number + i as i32 + 1, session_type, duration, now],
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn regenerate_pomodoro_sessions(conn: &Connection, task_id: i64, now: &str) -> Result<(), rusqlite::Error> {
+    // Sessões já iniciadas (em andamento, concluídas, puladas...) são histórico;
+    // apenas as pendentes posteriores são recriadas
+    let keep_until: i32 = conn.query_row(
+        "SELECT COALESCE(MAX(session_number), 0) FROM pomodoro_sessions
+         WHERE task_id = ?1 AND status != ?2",
+        rusqlite::params![task_id, SessionStatus::Pending],
+        |row| row.get(0),
+    )?;
+
+    conn.execute(
+        "DELETE FROM pomodoro_sessions WHERE task_id = ?1 AND session_number > ?2 AND status = ?3",
+        rusqlite::params![task_id, keep_until, SessionStatus::Pending],
+    )?;
+
+    create_pomodoro_cycles_after(conn, task_id, keep_until, now)?;
+
+    log::info!("Sessões Pomodoro regeneradas para tarefa {} a partir da sessão {}", task_id, keep_until + 1);
+    Ok(())
+}
+
+pub(crate) fn get_next_pomodoro_session(conn: &Connection, task_id: i64, now: &str) -> Result<Option<PomodoroSession>, rusqlite::Error> {
+    // Verificar se já existem sessões para esta tarefa
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pomodoro_sessions WHERE task_id = ?1",
+        [task_id],
+        |row| row.get(0),
+    )?;
+
+    // Se não existem sessões, criar os ciclos
+    if count == 0 {
+        create_pomodoro_cycles(conn, task_id, now)?;
+    }
+
+    // Buscar a próxima sessão a executar: a interrompida (pausa) ou a primeira pendente
+    let mut stmt = conn.prepare(
+        "SELECT ps.id, ps.task_id, ps.session_number, ps.session_type, ps.duration_seconds, ps.created_at,
+                ps.status, ps.started_at, ps.ended_at, ps.elapsed_seconds
+         FROM pomodoro_sessions ps
+         WHERE ps.task_id = ?1 AND ps.status IN (?2, ?3)
+         ORDER BY ps.session_number ASC
+         LIMIT 1"
+    )?;
+
+    let session_opt = stmt.query_row(rusqlite::params![task_id, SessionStatus::Pending, SessionStatus::Interrupted], |row| {
+        Ok(PomodoroSession {
+            id: Some(row.get(0)?),
+            task_id: row.get(1)?,
+            session_number: row.get(2)?,
+            session_type: row.get(3)?,
+            duration_seconds: row.get(4)?,
+            created_at: row.get(5)?,
+            status: row.get(6)?,
+            started_at: row.get(7)?,
+            ended_at: row.get(8)?,
+            elapsed_seconds: row.get(9)?,
+        })
+    }).optional()?;
+
+    Ok(session_opt)
+}
+
+pub(crate) fn start_pomodoro_session(conn: &Connection, task_id: i64, pomodoro_session: &PomodoroSession, now: &str) -> Result<TaskStatus, AppError> {
+    let pomodoro_id = pomodoro_session.id.unwrap();
+
+    // Inserir sessão ativa
+    conn.execute(
+        "INSERT OR REPLACE INTO active_sessions (task_id, pomodoro_id, started_at) VALUES (?1, ?2, ?3)",
+        [&task_id.to_string(), &pomodoro_id.to_string(), now],
+    )?;
+
+    // Marcar sessão como em execução (started_at guarda o primeiro início real)
+    conn.execute(
+        "UPDATE pomodoro_sessions
+         SET status = ?1, started_at = COALESCE(started_at, ?2), ended_at = NULL
+         WHERE id = ?3",
+        rusqlite::params![SessionStatus::Running, now, pomodoro_id],
+    )?;
+
+    // Status da tarefa acompanha o tipo da sessão
+    set_task_status(conn, task_id, TaskStatus::for_session(pomodoro_session.session_type), now)
+}
+
+// Deve ser chamada antes de remover a linha de active_sessions, de onde vem o início do trecho atual
+pub(crate) fn finish_running_pomodoro_session(conn: &Connection, task_id: i64, status: SessionStatus, ended_at: &str) -> Result<usize, rusqlite::Error> {
+    let active_started_at: Option<String> = conn.query_row(
+        "SELECT started_at FROM active_sessions WHERE task_id = ?1",
+        [task_id],
+        |row| row.get(0),
+    ).optional()?;
+
+    let elapsed_seconds = match active_started_at {
+        Some(started_at_str) => {
+            let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(0, "started_at".to_string(), rusqlite::types::Type::Text))?;
+            let ended = chrono::DateTime::parse_from_rfc3339(ended_at)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(0, "ended_at".to_string(), rusqlite::types::Type::Text))?;
+            ended.signed_duration_since(started_at).num_seconds().max(0)
+        }
+        None => 0,
+    };
+
+    conn.execute(
+        "UPDATE pomodoro_sessions
+         SET status = ?1, ended_at = ?2, elapsed_seconds = MIN(duration_seconds, elapsed_seconds + ?3)
+         WHERE task_id = ?4 AND status = ?5",
+        rusqlite::params![status, ended_at, elapsed_seconds, task_id, SessionStatus::Running],
+    )
+}
+
+pub(crate) fn skip_pending_pomodoro_sessions(conn: &Connection, task_id: i64) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        "UPDATE pomodoro_sessions SET status = ?2
+         WHERE task_id = ?1 AND status IN (?3, ?4)",
+        rusqlite::params![task_id, SessionStatus::Skipped, SessionStatus::Pending, SessionStatus::Interrupted],
+    )
+}
+
+pub fn check_and_advance_pomodoro_sessions(conn: &Connection, clock: &dyn Clock) -> Result<Vec<i64>, AppError> {
+    advance_pomodoro_sessions(conn, clock.now(), OfflinePolicy::Replay)
+}
+
+// Avança todas as sessões cujo prazo já passou, aplicando a política a cada fronteira perdida
+pub fn advance_pomodoro_sessions(conn: &Connection, now: chrono::DateTime<Utc>, policy: OfflinePolicy) -> Result<Vec<i64>, AppError> {
+    let mut advanced_tasks = Vec::new();
+
+    let mut stmt = conn.prepare("SELECT task_id FROM active_sessions")?;
+    let task_ids: Vec<i64> = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for task_id in task_ids {
+        // Cada tarefa avança atomicamente: um erro no meio não deixa a tarefa sem sessão
+        let advanced = with_savepoint(conn, || advance_task_pomodoro_sessions(conn, task_id, now, policy))?;
+
+        if advanced {
+            advanced_tasks.push(task_id);
+        }
+    }
+
+    Ok(advanced_tasks)
+}
+
+pub(crate) fn advance_task_pomodoro_sessions(
+    conn: &Connection,
+    task_id: i64,
+    now: chrono::DateTime<Utc>,
+    policy: OfflinePolicy,
+) -> Result<bool, AppError> {
+    let now_str = now.to_rfc3339();
+    let mut advanced = false;
+
+    while let Some((session, started_at_str)) = get_active_pomodoro_session(conn, task_id)? {
+        let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "started_at".to_string(), rusqlite::types::Type::Text))?;
+
+        let remaining_seconds = (session.duration_seconds - session.elapsed_seconds).max(0) as i64;
+        let session_end_time = started_at + chrono::Duration::seconds(remaining_seconds);
+
+        // Sessão ainda em andamento
+        if session_end_time > now {
+            break;
+        }
+
+        log::debug!("Sessão {} da tarefa {} terminou às {}",
+            session.session_number, task_id, session_end_time.to_rfc3339());
+
+        let session_end_str = session_end_time.to_rfc3339();
+        complete_pomodoro_session_at(conn, task_id, &session, &session_end_str)?;
+        advanced = true;
+
+        match policy {
+            OfflinePolicy::Replay => {
+                // Próxima sessão começa exatamente na fronteira; segue até alcançar o presente
+                if advance_to_next_pomodoro_session(conn, task_id, &session_end_str)?.is_none() {
+                    break;
+                }
+            }
+            OfflinePolicy::PauseAtBoundary => {
+                set_task_status(conn, task_id, TaskStatus::Paused, &session_end_str)?;
+                log::info!("Tarefa {} pausada na fronteira {}", task_id, session_end_str);
+                break;
+            }
+            OfflinePolicy::Discard => {
+                // Período perdido é descartado: próxima sessão começa agora
+                advance_to_next_pomodoro_session(conn, task_id, &now_str)?;
+                break;
+            }
+        }
+    }
+
+    Ok(advanced)
+}
+
+pub(crate) fn complete_pomodoro_session_at(conn: &Connection, task_id: i64, session: &PomodoroSession, ended_at: &str) -> Result<(), rusqlite::Error> {
+    // Remover sessão ativa atual
+    conn.execute(
+        "DELETE FROM active_sessions WHERE task_id = ?1",
+        [task_id],
+    )?;
+
+    // Registrar a conclusão no momento exato em que a sessão terminou
+    conn.execute(
+        "UPDATE pomodoro_sessions SET status = ?1, ended_at = ?2, elapsed_seconds = duration_seconds
+         WHERE id = ?3",
+        rusqlite::params![SessionStatus::Completed, ended_at, session.id.unwrap()],
+    )?;
+
+    // Finalizar log de tempo se for sessão de trabalho E se ainda não foi finalizado
+    if session.session_type == SessionType::Work {
+        let rows_updated = conn.execute(
+            "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
+            [ended_at, &task_id.to_string()],
+        )?;
+
+        if rows_updated > 0 {
+            log::debug!("Log de tempo finalizado automaticamente para tarefa {} às {}", task_id, ended_at);
+        } else {
+            log::debug!("Log já foi finalizado manualmente para tarefa {}, não sobrescrever", task_id);
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn advance_to_next_pomodoro_session(conn: &Connection, task_id: i64, now: &str) -> Result<Option<PomodoroSession>, AppError> {
+    let next_session = get_next_pomodoro_session(conn, task_id, now)?;
+
+    match &next_session {
+        Some(next_pomodoro) => {
+            // Iniciar próxima sessão automaticamente
+            start_pomodoro_session(conn, task_id, next_pomodoro, now)?;
+
+            // Voltando de uma pausa para o trabalho: abrir novo log de tempo
+            if next_pomodoro.session_type == SessionType::Work {
+                conn.execute(
+                    "INSERT INTO task_time_logs (task_id, started_at) VALUES (?1, ?2)",
+                    [&task_id.to_string(), now],
+                )?;
+            }
+
+            log::info!("Tarefa {} avançou para sessão: {} ({})",
+                task_id, next_pomodoro.session_type, next_pomodoro.session_number);
+        }
+        None => {
+            // Não há mais sessões, completar tarefa
+            set_task_status(conn, task_id, TaskStatus::Completed, now)?;
+
+            log::info!("Tarefa {} completada automaticamente - todos os Pomodoros finalizados", task_id);
+        }
+    }
+
+    Ok(next_session)
+}
+
+// Tempo restante da sessão e da tarefa, ambos calculados no backend: o frontend só exibe
+pub fn get_active_timers(conn: &Connection, clock: &dyn Clock) -> Result<Vec<TimerTickPayload>, rusqlite::Error> {
+    let now = clock.now();
+    let mut stmt = conn.prepare(
+        "SELECT a.task_id, a.pomodoro_id, a.started_at, p.session_number, p.session_type,
+                p.duration_seconds, p.elapsed_seconds, t.estimated_hours
+         FROM active_sessions a
+         JOIN pomodoro_sessions p ON a.pomodoro_id = p.id
+         JOIN tasks t ON a.task_id = t.id"
+    )?;
+
+    let timers = stmt.query_map([], |row| {
+        let task_id: i64 = row.get(0)?;
+        let started_at_str: String = row.get(2)?;
+        let duration_seconds: i32 = row.get(5)?;
+        let elapsed_seconds: i32 = row.get(6)?;
+
+        let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(2, "started_at".to_string(), rusqlite::types::Type::Text))?;
+        let ends_at = started_at + chrono::Duration::seconds((duration_seconds - elapsed_seconds).max(0) as i64);
+
+        Ok(TimerTickPayload {
+            task_id,
+            pomodoro_id: row.get(1)?,
+            session_number: row.get(3)?,
+            session_type: row.get(4)?,
+            remaining_seconds: ends_at.signed_duration_since(now).num_seconds().max(0),
+            ends_at: ends_at.to_rfc3339(),
+            task_remaining_seconds: calculate_task_remaining_time(conn, clock, task_id, row.get(7)?)?,
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+
+    Ok(timers)
+}
+
+pub fn get_session_transition(conn: &Connection, task_id: i64) -> Result<SessionTransitionPayload, rusqlite::Error> {
+    let task_status: TaskStatus = conn.query_row(
+        "SELECT status FROM tasks WHERE id = ?1",
+        [task_id],
+        |row| row.get(0),
+    )?;
+    let active = get_active_pomodoro_session(conn, task_id)?;
+
+    Ok(SessionTransitionPayload {
+        task_id,
+        task_status,
+        session_number: active.as_ref().map(|(session, _)| session.session_number),
+        session_type: active.map(|(session, _)| session.session_type),
+    })
+}
+
+pub fn get_active_pomodoro_session(conn: &Connection, task_id: i64) -> Result<Option<(PomodoroSession, String)>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT ps.id, ps.task_id, ps.session_number, ps.session_type, ps.duration_seconds, ps.created_at,
+                ps.status, ps.started_at, ps.ended_at, ps.elapsed_seconds, a.started_at
+         FROM active_sessions a
+         JOIN pomodoro_sessions ps ON a.pomodoro_id = ps.id
+         WHERE a.task_id = ?1",
+        [task_id],
+        |row| {
+            Ok((
+                PomodoroSession {
+                    id: Some(row.get(0)?),
+                    task_id: row.get(1)?,
+                    session_number: row.get(2)?,
+                    session_type: row.get(3)?,
+                    duration_seconds: row.get(4)?,
+                    created_at: row.get(5)?,
+                    status: row.get(6)?,
+                    started_at: row.get(7)?,
+                    ended_at: row.get(8)?,
+                    elapsed_seconds: row.get(9)?,
+                },
+                row.get(10)?, // início do trecho atual
+            ))
+        },
+    ).optional()
+}
+
+pub(crate) fn record_session_action(
+    conn: &Connection,
+    task_id: i64,
+    pomodoro_id: i64,
+    action: &str,
+    amount_seconds: Option<i64>,
+    created_at: &str,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO pomodoro_session_history (task_id, pomodoro_id, action, amount_seconds, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![task_id, pomodoro_id, action, amount_seconds, created_at],
+    )?;
+    Ok(())
+}
+
+pub fn skip_active_pomodoro_session(conn: &Connection, clock: &dyn Clock, task_id: i64) -> Result<Option<PomodoroSession>, AppError> {
+    let now = clock.now().to_rfc3339();
+
+    let (session, _) = get_active_pomodoro_session(conn, task_id)?
+        .ok_or(AppError::NoActiveSession { task_id })?;
+    let pomodoro_id = session.id.unwrap();
+
+    finish_running_pomodoro_session(conn, task_id, SessionStatus::Skipped, &now)?;
+    conn.execute("DELETE FROM active_sessions WHERE task_id = ?1", [task_id])?;
+
+    // Trabalho pulado: o tempo já feito continua registrado, mas o log termina agora
+    if session.session_type == SessionType::Work {
+        conn.execute(
+            "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
+            [&now, &task_id.to_string()],
+        )?;
+    }
+
+    record_session_action(conn, task_id, pomodoro_id, "skip", None, &now)?;
+
+    advance_to_next_pomodoro_session(conn, task_id, &now)
+}
+
+pub fn extend_active_pomodoro_session(conn: &Connection, clock: &dyn Clock, task_id: i64, seconds: i64) -> Result<(), AppError> {
+    if seconds <= 0 {
+        return Err(AppError::validation("seconds", "A extensão deve ser maior que zero"));
+    }
+
+    let now = clock.now().to_rfc3339();
+    let (session, _) = get_active_pomodoro_session(conn, task_id)?
+        .ok_or(AppError::NoActiveSession { task_id })?;
+    let pomodoro_id = session.id.unwrap();
+
+    // O log de tempo aberto continua valendo; só o fim da sessão é adiado. A extensão fica
+    // registrada à parte para não entrar no trabalho planejado ao recalcular o plano
+    conn.execute(
+        "UPDATE pomodoro_sessions
+         SET duration_seconds = duration_seconds + ?1, extended_seconds = extended_seconds + ?1
+         WHERE id = ?2",
+        [seconds, pomodoro_id],
+    )?;
+
+    record_session_action(conn, task_id, pomodoro_id, "extend", Some(seconds), &now)?;
+    Ok(())
+}
+
+pub fn restart_active_pomodoro_session(conn: &Connection, clock: &dyn Clock, task_id: i64) -> Result<(), AppError> {
+    let now = clock.now().to_rfc3339();
+    let (session, _) = get_active_pomodoro_session(conn, task_id)?
+        .ok_or(AppError::NoActiveSession { task_id })?;
+    let pomodoro_id = session.id.unwrap();
+
+    // Sessão volta ao início; o trabalho já feito permanece nos logs de tempo
+    conn.execute(
+        "UPDATE pomodoro_sessions SET elapsed_seconds = 0 WHERE id = ?1",
+        [pomodoro_id],
+    )?;
+    conn.execute(
+        "UPDATE active_sessions SET started_at = ?1 WHERE task_id = ?2",
+        [&now, &task_id.to_string()],
+    )?;
+
+    record_session_action(conn, task_id, pomodoro_id, "restart", None, &now)?;
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::clock::Clock;
+use crate::error::AppError;
+use crate::models::PomodoroProfile;
+use crate::pomodoro::regenerate_pomodoro_sessions;
+
+pub(crate) fn classic_pomodoro_profile() -> PomodoroProfile {
+    // Ciclo padrão Pomodoro: 25min trabalho, 5min pausa, repetir 4x, depois 15min pausa longa
+    PomodoroProfile {
+        id: None,
+        name: "Classic 25/5".to_string(),
+        work_seconds: 25 * 60,
+        short_break_seconds: 5 * 60,
+        long_break_seconds: 15 * 60,
+        rounds_before_long_break: 4,
+        total_rounds: 4,
+        is_default: true,
+    }
+}
+
+pub(crate) fn row_to_pomodoro_profile(row: &rusqlite::Row) -> Result<PomodoroProfile, rusqlite::Error> {
+    Ok(PomodoroProfile {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        work_seconds: row.get(2)?,
+        short_break_seconds: row.get(3)?,
+        long_break_seconds: row.get(4)?,
+        rounds_before_long_break: row.get(5)?,
+        total_rounds: row.get(6)?,
+        is_default: row.get(7)?,
+    })
+}
+
+pub fn get_pomodoro_profile(conn: &Connection, profile_id: i64) -> Result<Option<PomodoroProfile>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT id, name, work_seconds, short_break_seconds, long_break_seconds,
+                rounds_before_long_break, total_rounds, is_default
+         FROM pomodoro_profiles WHERE id = ?1",
+        [profile_id],
+        row_to_pomodoro_profile,
+    ).optional()
+}
+
+pub fn get_default_pomodoro_profile(conn: &Connection) -> Result<PomodoroProfile, rusqlite::Error> {
+    let profile = conn.query_row(
+        "SELECT id, name, work_seconds, short_break_seconds, long_break_seconds,
+                rounds_before_long_break, total_rounds, is_default
+         FROM pomodoro_profiles WHERE is_default = 1
+         ORDER BY id ASC LIMIT 1",
+        [],
+        row_to_pomodoro_profile,
+    ).optional()?;
+
+    Ok(profile.unwrap_or_else(classic_pomodoro_profile))
+}
+
+pub(crate) fn get_task_pomodoro_profile(conn: &Connection, task_id: i64) -> Result<PomodoroProfile, rusqlite::Error> {
+    let profile_id: Option<i64> = conn.query_row(
+        "SELECT profile_id FROM tasks WHERE id = ?1",
+        [task_id],
+        |row| row.get(0),
+    ).optional()?.flatten();
+
+    match profile_id {
+        Some(id) => match get_pomodoro_profile(conn, id)? {
+            Some(profile) => Ok(profile),
+            None => get_default_pomodoro_profile(conn),
+        },
+        None => get_default_pomodoro_profile(conn),
+    }
+}
+
+pub(crate) fn validate_pomodoro_profile(profile: &PomodoroProfile) -> Result<(), AppError> {
+    if profile.name.trim().is_empty() {
+        return Err(AppError::validation("name", "O nome do perfil não pode ser vazio"));
+    }
+
+    if profile.work_seconds <= 0 {
+        return Err(AppError::validation("work_seconds", "A duração do trabalho deve ser maior que zero"));
+    }
+
+    if profile.short_break_seconds < 0 || profile.long_break_seconds < 0 {
+        return Err(AppError::validation("break_seconds", "As pausas não podem ter duração negativa"));
+    }
+
+    if profile.rounds_before_long_break <= 0 || profile.total_rounds <= 0 {
+        return Err(AppError::validation("rounds", "O número de rodadas deve ser maior que zero"));
+    }
+
+    Ok(())
+}
+
+pub fn set_task_pomodoro_profile(conn: &Connection, task_id: i64, profile_id: Option<i64>, now: &str) -> Result<(), AppError> {
+    if let Some(id) = profile_id {
+        get_pomodoro_profile(conn, id)?
+            .ok_or(AppError::ProfileNotFound { profile_id: id })?;
+    }
+
+    let current: Option<i64> = conn.query_row(
+        "SELECT profile_id FROM tasks WHERE id = ?1",
+        [task_id],
+        |row| row.get(0),
+    ).optional()?
+    .ok_or(AppError::TaskNotFound { task_id })?;
+
+    if current == profile_id {
+        return Ok(());
+    }
+
+    conn.execute(
+        "UPDATE tasks SET profile_id = ?1 WHERE id = ?2",
+        rusqlite::params![profile_id, task_id],
+    )?;
+
+    regenerate_pomodoro_sessions(conn, task_id, now)?;
+    Ok(())
+}
+
+pub fn list_pomodoro_profiles(conn: &Connection) -> Result<Vec<PomodoroProfile>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, work_seconds, short_break_seconds, long_break_seconds,
+                rounds_before_long_break, total_rounds, is_default
+         FROM pomodoro_profiles ORDER BY id ASC"
+    )?;
+
+    let profiles = stmt.query_map([], row_to_pomodoro_profile)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(profiles)
+}
+
+// Perfis criados pelo usuário nunca nascem como padrão
+pub fn create_pomodoro_profile(conn: &Connection, clock: &dyn Clock, profile: PomodoroProfile) -> Result<PomodoroProfile, AppError> {
+    let mut profile = PomodoroProfile {
+        id: None,
+        name: profile.name.trim().to_string(),
+        is_default: false,
+        ..profile
+    };
+    validate_pomodoro_profile(&profile)?;
+
+    conn.execute(
+        "INSERT INTO pomodoro_profiles
+            (name, work_seconds, short_break_seconds, long_break_seconds,
+             rounds_before_long_break, total_rounds, is_default, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7)",
+        rusqlite::params![
+            profile.name,
+            profile.work_seconds,
+            profile.short_break_seconds,
+            profile.long_break_seconds,
+            profile.rounds_before_long_break,
+            profile.total_rounds,
+            clock.now().to_rfc3339(),
+        ],
+    )?;
+
+    profile.id = Some(conn.last_insert_rowid());
+    log::info!("Perfil Pomodoro '{}' criado", profile.name);
+
+    Ok(profile)
+}
+
+pub fn delete_pomodoro_profile(conn: &Connection, profile_id: i64) -> Result<(), AppError> {
+    let profile = get_pomodoro_profile(conn, profile_id)?
+        .ok_or(AppError::ProfileNotFound { profile_id })?;
+
+    if profile.is_default {
+        return Err(AppError::DefaultProfileLocked { profile_id });
+    }
+
+    // Tarefas que usavam o perfil voltam para o padrão (sessões já criadas são mantidas)
+    conn.execute(
+        "UPDATE tasks SET profile_id = NULL WHERE profile_id = ?1",
+        [profile_id],
+    )?;
+
+    conn.execute(
+        "DELETE FROM pomodoro_profiles WHERE id = ?1",
+        [profile_id],
+    )?;
+
+    Ok(())
+}
+
+pub fn set_default_pomodoro_profile(conn: &Connection, profile_id: i64) -> Result<(), AppError> {
+    get_pomodoro_profile(conn, profile_id)?
+        .ok_or(AppError::ProfileNotFound { profile_id })?;
+
+    conn.execute(
+        "UPDATE pomodoro_profiles SET is_default = CASE WHEN id = ?1 THEN 1 ELSE 0 END",
+        [profile_id],
+    )?;
+
+    log::info!("Perfil Pomodoro {} definido como padrão", profile_id);
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Connection;
+
+use crate::clock::Clock;
+use crate::error::AppError;
+use crate::models::{RecoveredTimeLog, RecoverySummary};
+use crate::settings::{delete_setting, get_setting, set_setting};
+use crate::tasks::pause_task_at;
+
+// Frequência com que o motor de timer confirma os logs abertos
+pub const HEARTBEAT_INTERVAL_SECONDS: i64 = 30;
+
+// Sem batimento por mais que isso, o log aberto ficou de um app que caiu ou foi morto
+pub const HEARTBEAT_STALE_SECONDS: i64 = 3 * HEARTBEAT_INTERVAL_SECONDS;
+
+const CLEAN_SHUTDOWN_KEY: &str = "clean_shutdown_at";
+
+pub fn write_heartbeat(conn: &Connection, now: DateTime<Utc>) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        "UPDATE task_time_logs SET heartbeat_at = ?1 WHERE ended_at IS NULL",
+        [now.to_rfc3339()],
+    )
+}
+
+// Saída normal: na inicialização seguinte os logs abertos são fechados neste horário,
+// para o tempo com o app fechado não contar como trabalho (nem ser reproduzido pelo Replay)
+pub fn mark_clean_shutdown(conn: &Connection, clock: &dyn Clock) -> Result<(), rusqlite::Error> {
+    set_setting(conn, CLEAN_SHUTDOWN_KEY, &clock.now().to_rfc3339())
+}
+
+fn parse_timestamp(value: &str, column: &str) -> Result<DateTime<Utc>, rusqlite::Error> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| rusqlite::Error::InvalidColumnType(0, column.to_string(), rusqlite::types::Type::Text))
+}
+
+// Tarefa com sessão ativa pausa pelo caminho de pause_task; log sem sessão só é fechado
+fn close_open_log(conn: &Connection, log_id: i64, task_id: i64, closed_at: &str) -> Result<(), AppError> {
+    let has_active_session: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM active_sessions WHERE task_id = ?1",
+        [task_id],
+        |row| row.get(0),
+    )?;
+
+    if has_active_session {
+        pause_task_at(conn, task_id, closed_at)?;
+    } else {
+        conn.execute(
+            "UPDATE task_time_logs SET ended_at = ?1 WHERE id = ?2 AND ended_at IS NULL",
+            rusqlite::params![closed_at, log_id],
+        )?;
+    }
+    Ok(())
+}
+
+// Depois de uma saída normal, o trabalho parou no horário registrado em mark_clean_shutdown
+fn close_time_logs_at_shutdown(conn: &Connection, shutdown_at: &str) -> Result<usize, AppError> {
+    let shutdown = parse_timestamp(shutdown_at, CLEAN_SHUTDOWN_KEY)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, started_at FROM task_time_logs WHERE ended_at IS NULL ORDER BY task_id, started_at"
+    )?;
+    let open_logs = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?))
+    })?.collect::<Result<Vec<_>, _>>()?;
+
+    for (log_id, task_id, started_at) in &open_logs {
+        let closed_at = shutdown.max(parse_timestamp(started_at, "started_at")?).to_rfc3339();
+        close_open_log(conn, *log_id, *task_id, &closed_at)?;
+        log::info!("Log {} da tarefa {} fechado no encerramento do app ({})", log_id, task_id, closed_at);
+    }
+
+    Ok(open_logs.len())
+}
+
+// Na inicialização, antes de avançar as sessões: depois de uma saída normal, fecha os logs abertos
+// no horário do encerramento; depois de uma queda, fecha no último batimento (pausando a tarefa
+// pelo caminho de pause_task) em vez de contar o tempo parado.
+pub fn recover_stale_time_logs(conn: &Connection, clock: &dyn Clock) -> Result<Option<RecoverySummary>, AppError> {
+    let clean_shutdown = get_setting(conn, CLEAN_SHUTDOWN_KEY)?;
+    delete_setting(conn, CLEAN_SHUTDOWN_KEY)?;
+
+    if let Some(shutdown_at) = clean_shutdown {
+        let closed = close_time_logs_at_shutdown(conn, &shutdown_at)?;
+        log::debug!("App encerrado normalmente em {}; {} log(s) aberto(s) fechado(s)", shutdown_at, closed);
+        return Ok(None);
+    }
+
+    let now = clock.now();
+    let stale_after = Duration::seconds(HEARTBEAT_STALE_SECONDS);
+
+    let mut stmt = conn.prepare(
+        "SELECT l.id, l.task_id, t.name, l.started_at, l.heartbeat_at
+         FROM task_time_logs l
+         JOIN tasks t ON t.id = l.task_id
+         WHERE l.ended_at IS NULL
+         ORDER BY l.task_id, l.started_at"
+    )?;
+    let open_logs = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, Option<String>>(4)?,
+        ))
+    })?.collect::<Result<Vec<_>, _>>()?;
+
+    let mut logs = Vec::new();
+    for (log_id, task_id, task_name, started_at, heartbeat_at) in open_logs {
+        // Sem batimento nenhum, só dá para garantir o início do log
+        let last_seen = parse_timestamp(heartbeat_at.as_deref().unwrap_or(&started_at), "heartbeat_at")?
+            .max(parse_timestamp(&started_at, "started_at")?);
+        if now - last_seen < stale_after {
+            continue;
+        }
+
+        let closed_at = last_seen.to_rfc3339();
+        close_open_log(conn, log_id, task_id, &closed_at)?;
+
+        log::warn!("Log {} da tarefa {} estava aberto desde a queda do app; fechado em {}", log_id, task_id, closed_at);
+        logs.push(RecoveredTimeLog {
+            log_id,
+            task_id,
+            task_name,
+            started_at,
+            closed_at,
+            discarded_seconds: (now - last_seen).num_seconds(),
+        });
+    }
+
+    if logs.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(RecoverySummary { recovered_at: now.to_rfc3339(), logs }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+    use crate::database::init_in_memory_database;
+    use crate::status::TaskStatus;
+    use crate::tasks::{get_task_by_id, start_task};
+    use crate::time_logs::get_task_remaining_time;
+
+    // Tarefa de 1 h iniciada às 09:00, com batimentos por 10 minutos antes da "queda"
+    fn crashed_after_ten_minutes() -> (Connection, FakeClock, i64) {
+        let conn = init_in_memory_database().unwrap();
+        let clock = FakeClock::new("2026-01-01T09:00:00Z".parse().unwrap());
+        conn.execute(
+            "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at)
+             VALUES ('tarefa', 'teste', 1.0, '2026-01-01', 'pending', '2026-01-01T08:00:00+00:00')",
+            [],
+        ).unwrap();
+        let task_id = conn.last_insert_rowid();
+        start_task(&conn, &clock, task_id, None, None).unwrap();
+
+        for _ in 0..20 {
+            clock.advance(Duration::seconds(HEARTBEAT_INTERVAL_SECONDS));
+            write_heartbeat(&conn, clock.now()).unwrap();
+        }
+        (conn, clock, task_id)
+    }
+
+    #[test]
+    fn stale_open_log_is_closed_at_the_last_heartbeat() {
+        let (conn, clock, task_id) = crashed_after_ten_minutes();
+        clock.advance(Duration::hours(8));
+
+        let summary = recover_stale_time_logs(&conn, &clock).unwrap().unwrap();
+        assert_eq!(summary.logs.len(), 1);
+        assert_eq!(summary.logs[0].task_id, task_id);
+        assert_eq!(summary.logs[0].closed_at, "2026-01-01T09:10:00+00:00");
+        assert_eq!(summary.logs[0].discarded_seconds, 8 * 3600);
+
+        assert_eq!(get_task_by_id(&conn, task_id).unwrap().unwrap().status, TaskStatus::Paused);
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 50 * 60);
+
+        // Já recuperado: uma segunda inicialização não encontra nada
+        assert_eq!(recover_stale_time_logs(&conn, &clock).unwrap(), None);
+    }
+
+    #[test]
+    fn quick_restart_keeps_the_log_open() {
+        let (conn, clock, task_id) = crashed_after_ten_minutes();
+
+        clock.advance(Duration::seconds(HEARTBEAT_STALE_SECONDS - 1));
+        assert_eq!(recover_stale_time_logs(&conn, &clock).unwrap(), None);
+        assert_eq!(get_task_by_id(&conn, task_id).unwrap().unwrap().status, TaskStatus::InProgress);
+
+        // Sem aviso de saída normal, a próxima inicialização tardia ainda recupera o log
+        clock.advance(Duration::hours(8));
+        assert!(recover_stale_time_logs(&conn, &clock).unwrap().is_some());
+    }
+
+    #[test]
+    fn clean_shutdown_closes_the_log_at_the_shutdown_time() {
+        let (conn, clock, task_id) = crashed_after_ten_minutes();
+
+        clock.advance(Duration::seconds(20));
+        mark_clean_shutdown(&conn, &clock).unwrap();
+        clock.advance(Duration::hours(8));
+
+        // Saída normal não gera aviso, mas o tempo com o app fechado não conta
+        assert_eq!(recover_stale_time_logs(&conn, &clock).unwrap(), None);
+        assert_eq!(get_task_by_id(&conn, task_id).unwrap().unwrap().status, TaskStatus::Paused);
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 50 * 60 - 20);
+
+        let open_logs: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM task_time_logs WHERE ended_at IS NULL", [], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(open_logs, 0);
+        let active_sessions: i64 = conn.query_row("SELECT COUNT(*) FROM active_sessions", [], |row| row.get(0)).unwrap();
+        assert_eq!(active_sessions, 0);
+
+        // O aviso de saída normal vale só para a inicialização seguinte
+        assert_eq!(recover_stale_time_logs(&conn, &clock).unwrap(), None);
+    }
+}
@@ -0,0 +1,172 @@
+use chrono_tz::Tz;
+use log::LevelFilter;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+// 5 minutos sem teclado/mouse pausam a tarefa em andamento; 0 desliga a detecção
+pub const DEFAULT_IDLE_THRESHOLD_SECONDS: i64 = 5 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflinePolicy {
+    Replay,          // reproduz todas as transições nos horários exatos
+    PauseAtBoundary, // para na primeira fronteira perdida e pausa a tarefa
+    Discard,         // descarta o período offline e começa a próxima sessão agora
+}
+
+impl OfflinePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OfflinePolicy::Replay => "replay",
+            OfflinePolicy::PauseAtBoundary => "pause_at_boundary",
+            OfflinePolicy::Discard => "discard",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<OfflinePolicy> {
+        match value {
+            "replay" => Some(OfflinePolicy::Replay),
+            "pause_at_boundary" => Some(OfflinePolicy::PauseAtBoundary),
+            "discard" => Some(OfflinePolicy::Discard),
+            _ => None,
+        }
+    }
+}
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [key],
+        |row| row.get(0),
+    ).optional()
+}
+
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+pub fn delete_setting(conn: &Connection, key: &str) -> Result<(), rusqlite::Error> {
+    conn.execute("DELETE FROM app_settings WHERE key = ?1", [key])?;
+    Ok(())
+}
+
+pub fn load_offline_policy(conn: &Connection) -> Result<OfflinePolicy, rusqlite::Error> {
+    Ok(get_setting(conn, "offline_policy")?
+        .and_then(|value| OfflinePolicy::parse(&value))
+        .unwrap_or(OfflinePolicy::Replay))
+}
+
+pub fn load_log_level(conn: &Connection) -> Result<LevelFilter, rusqlite::Error> {
+    Ok(get_setting(conn, "log_level")?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_LOG_LEVEL))
+}
+
+// Aceita os nomes do crate log ("off", "error", "warn", "info", "debug", "trace")
+pub fn save_log_level(conn: &Connection, level: &str) -> Result<LevelFilter, AppError> {
+    let level: LevelFilter = level.trim().parse()
+        .map_err(|_| AppError::validation("level", format!("Nível de log inválido: {}", level)))?;
+
+    set_setting(conn, "log_level", &level.as_str().to_lowercase())?;
+    Ok(level)
+}
+
+// None quando a detecção de inatividade está desligada
+pub fn load_idle_threshold(conn: &Connection) -> Result<Option<chrono::Duration>, rusqlite::Error> {
+    let seconds = get_setting(conn, "idle_threshold_seconds")?
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_IDLE_THRESHOLD_SECONDS);
+
+    Ok((seconds > 0).then(|| chrono::Duration::seconds(seconds)))
+}
+
+pub fn save_idle_threshold(conn: &Connection, seconds: i64) -> Result<(), AppError> {
+    if seconds < 0 {
+        return Err(AppError::validation("seconds", "O tempo de inatividade não pode ser negativo"));
+    }
+
+    set_setting(conn, "idle_threshold_seconds", &seconds.to_string())?;
+    Ok(())
+}
+
+// Fuso detectado no sistema (TZ ou /etc/localtime); UTC quando não dá para identificar
+pub fn system_timezone() -> Tz {
+    iana_time_zone::get_timezone().ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+pub fn parse_timezone(name: &str) -> Result<Tz, AppError> {
+    name.trim().parse()
+        .map_err(|_| AppError::validation("timezone", format!("Fuso horário inválido: {} (esperado nome IANA, ex.: America/Sao_Paulo)", name)))
+}
+
+// Fuso do usuário para fronteiras de dia; sem configuração, o do sistema
+pub fn load_timezone(conn: &Connection) -> Result<Tz, rusqlite::Error> {
+    Ok(get_setting(conn, "timezone")?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(system_timezone))
+}
+
+pub fn save_timezone(conn: &Connection, name: &str) -> Result<Tz, AppError> {
+    let tz = parse_timezone(name)?;
+    set_setting(conn, "timezone", tz.name())?;
+    Ok(tz)
+}
+
+// Na primeira execução grava o fuso do sistema: viajar depois não muda os dias já registrados
+pub fn ensure_timezone(conn: &Connection) -> Result<Tz, rusqlite::Error> {
+    if let Some(tz) = get_setting(conn, "timezone")?.and_then(|value| value.parse().ok()) {
+        return Ok(tz);
+    }
+
+    let tz = system_timezone();
+    set_setting(conn, "timezone", tz.name())?;
+    Ok(tz)
+}
+
+// Fuso explícito do comando quando informado; senão o gravado por ensure_timezone/save_timezone
+// (o do sistema só se nada foi gravado). Fuso explícito inválido é erro de validação
+pub fn resolve_timezone(conn: &Connection, timezone: Option<&str>) -> Result<Tz, AppError> {
+    match timezone {
+        Some(name) => parse_timezone(name),
+        None => Ok(load_timezone(conn)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::init_in_memory_database;
+
+    #[test]
+    fn missing_timezone_falls_back_to_the_stored_setting() {
+        let conn = init_in_memory_database().unwrap();
+        assert_eq!(resolve_timezone(&conn, None).unwrap(), system_timezone());
+
+        save_timezone(&conn, "Asia/Tokyo").unwrap();
+        assert_eq!(resolve_timezone(&conn, None).unwrap(), chrono_tz::Asia::Tokyo);
+
+        // Explícito vence o gravado, sem alterá-lo
+        assert_eq!(resolve_timezone(&conn, Some("America/Sao_Paulo")).unwrap(), chrono_tz::America::Sao_Paulo);
+        assert_eq!(load_timezone(&conn).unwrap(), chrono_tz::Asia::Tokyo);
+    }
+
+    #[test]
+    fn invalid_explicit_timezone_is_rejected_instead_of_replaced() {
+        let conn = init_in_memory_database().unwrap();
+        save_timezone(&conn, "Asia/Tokyo").unwrap();
+
+        let error = resolve_timezone(&conn, Some("Marte/Olympus")).unwrap_err();
+        assert!(matches!(error, AppError::Validation { field: "timezone", .. }));
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt;
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Pending,    // criada, nunca iniciada
+    InProgress, // em sessão de trabalho
+    Waiting,    // em sessão de pausa
+    Paused,     // interrompida pelo usuário
+    Completed,
+}
+
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Pending => "pending",
+            TaskStatus::InProgress => "in_progress",
+            TaskStatus::Waiting => "waiting",
+            TaskStatus::Paused => "paused",
+            TaskStatus::Completed => "completed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<TaskStatus> {
+        match value {
+            "pending" => Some(TaskStatus::Pending),
+            "in_progress" => Some(TaskStatus::InProgress),
+            "waiting" => Some(TaskStatus::Waiting),
+            "paused" => Some(TaskStatus::Paused),
+            "completed" => Some(TaskStatus::Completed),
+            _ => None,
+        }
+    }
+
+    // Status da tarefa enquanto uma sessão do tipo informado está rodando
+    pub fn for_session(session_type: SessionType) -> TaskStatus {
+        match session_type {
+            SessionType::Work => TaskStatus::InProgress,
+            SessionType::Break => TaskStatus::Waiting,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self, TaskStatus::InProgress | TaskStatus::Waiting)
+    }
+
+    // Única definição do ciclo de vida da tarefa
+    pub fn transition(self, next: TaskStatus) -> Result<TaskStatus, InvalidTransition> {
+        let allowed = match (self, next) {
+            // Concluída é final e nenhuma tarefa volta a ser "nunca iniciada"
+            (TaskStatus::Completed, _) | (_, TaskStatus::Pending) => false,
+            // Reaplicar o mesmo status (ex.: trabalho seguido de trabalho) não muda nada
+            (current, next) if current == next => true,
+            (_, TaskStatus::InProgress) | (_, TaskStatus::Waiting) | (_, TaskStatus::Completed) => true,
+            (current, TaskStatus::Paused) => current.is_running(),
+        };
+
+        if allowed {
+            Ok(next)
+        } else {
+            Err(InvalidTransition { from: self, to: next })
+        }
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ToSql for TaskStatus {
+    fn to_sql(&self) -> SqliteResult<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for TaskStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        TaskStatus::parse(text).ok_or_else(|| FromSqlError::Other(format!("Status de tarefa desconhecido: {}", text).into()))
+    }
+}
+
+// Situação de uma sessão Pomodoro planejada; no máximo uma "running" por tarefa
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Pending,     // ainda não começou
+    Running,     // apontada por active_sessions
+    Completed,   // cumpriu a duração
+    Skipped,     // pulada pelo usuário ou descartada ao concluir a tarefa
+    Interrupted, // pausada no meio; retoma com o tempo que faltava
+}
+
+impl SessionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStatus::Pending => "pending",
+            SessionStatus::Running => "running",
+            SessionStatus::Completed => "completed",
+            SessionStatus::Skipped => "skipped",
+            SessionStatus::Interrupted => "interrupted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<SessionStatus> {
+        match value {
+            "pending" => Some(SessionStatus::Pending),
+            "running" => Some(SessionStatus::Running),
+            "completed" => Some(SessionStatus::Completed),
+            "skipped" => Some(SessionStatus::Skipped),
+            "interrupted" => Some(SessionStatus::Interrupted),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SessionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ToSql for SessionStatus {
+    fn to_sql(&self) -> SqliteResult<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for SessionStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        SessionStatus::parse(text).ok_or_else(|| FromSqlError::Other(format!("Status de sessão desconhecido: {}", text).into()))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionType {
+    Work,
+    Break,
+}
+
+impl SessionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionType::Work => "work",
+            SessionType::Break => "break",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<SessionType> {
+        match value {
+            "work" => Some(SessionType::Work),
+            "break" => Some(SessionType::Break),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SessionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ToSql for SessionType {
+    fn to_sql(&self) -> SqliteResult<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for SessionType {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        SessionType::parse(text).ok_or_else(|| FromSqlError::Other(format!("Tipo de sessão desconhecido: {}", text).into()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidTransition {
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+}
+
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.from, self.to) {
+            (TaskStatus::Completed, TaskStatus::Completed) => write!(f, "Tarefa já está concluída"),
+            (TaskStatus::Completed, _) => write!(f, "Tarefa concluída não pode ser retomada"),
+            (from, to) => write!(f, "Transição de status inválida: {} → {}", from.as_str(), to.as_str()),
+        }
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
@@ -0,0 +1,403 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+use crate::clock::Clock;
+use crate::error::AppError;
+use crate::status::TaskStatus;
+use crate::tasks::{find_running_task_segment, pause_task_at, resume_task_at};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemEventKind {
+    Sleep,
+    Wake,
+    Lock,
+    Unlock,
+    Shutdown,
+    ShutdownCancelled,
+}
+
+// Evento do sistema com o horário em que o sinal chegou ao monitor (não o horário em que o banco o processou)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystemEvent {
+    pub kind: SystemEventKind,
+    pub at: DateTime<Utc>,
+}
+
+pub trait SystemEventSource: Send {
+    // Bloqueia até o próximo evento; None quando a fonte encerrou
+    fn next_event(&mut self) -> Result<Option<SystemEvent>, AppError>;
+
+    // Chamado depois que o evento foi gravado no banco (ex.: liberar o sistema para suspender)
+    fn event_handled(&mut self, _event: &SystemEvent) {}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bus {
+    System,
+    // Barramento privado (endereço do dbus-daemon): usado nos testes para simular o logind sem privilégios
+    Address(String),
+}
+
+const LOGIND_NAME: &str = "org.freedesktop.login1";
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+
+// Sinais do logind assinados no D-Bus (PrepareForSleep, PrepareForShutdown e Lock/Unlock da própria sessão).
+// Segura um inibidor "delay": o sistema espera o log ser fechado antes de suspender ou desligar
+pub struct LogindMonitor {
+    messages: zbus::blocking::MessageIterator,
+    manager: zbus::blocking::Proxy<'static>,
+    session_path: Option<String>,
+    inhibitor: Option<zbus::zvariant::OwnedFd>,
+    clock: Arc<dyn Clock>,
+}
+
+impl LogindMonitor {
+    pub fn connect(bus: Bus, clock: Arc<dyn Clock>) -> Result<LogindMonitor, AppError> {
+        let conn = match bus {
+            Bus::System => zbus::blocking::Connection::system(),
+            Bus::Address(address) => zbus::blocking::connection::Builder::address(address.as_str())
+                .and_then(|builder| builder.build()),
+        }.map_err(system_error)?;
+
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender(LOGIND_NAME)
+            .and_then(|builder| builder.path_namespace(MANAGER_PATH))
+            .map_err(system_error)?
+            .build();
+        let messages = zbus::blocking::MessageIterator::for_match_rule(rule, &conn, None).map_err(system_error)?;
+
+        let manager = zbus::blocking::proxy::Builder::new(&conn)
+            .destination(LOGIND_NAME)
+            .and_then(|builder| builder.path(MANAGER_PATH))
+            .and_then(|builder| builder.interface(MANAGER_INTERFACE))
+            .map_err(system_error)?
+            .cache_properties(zbus::proxy::CacheProperties::No)
+            .build()
+            .map_err(system_error)?;
+
+        // Lock/Unlock chegam para todas as sessões do sistema; só a deste processo interessa
+        let session_path = match session_path(&manager) {
+            Ok(path) => {
+                log::info!(target: "system", "Sessão do logind: {}", path);
+                Some(path)
+            }
+            Err(e) => {
+                log::warn!(target: "system", "Sessão do logind não encontrada; bloqueio de tela ignorado: {}", e);
+                None
+            }
+        };
+
+        let inhibitor = take_inhibitor(&manager);
+        Ok(LogindMonitor { messages, manager, session_path, inhibitor, clock })
+    }
+}
+
+// Sem inibidor (ex.: sem permissão) os eventos ainda chegam, mas o sistema pode suspender antes da gravação
+fn take_inhibitor(manager: &zbus::blocking::Proxy<'_>) -> Option<zbus::zvariant::OwnedFd> {
+    manager.call("Inhibit", &("sleep:shutdown", "ClockWise", "Fechar o registro de tempo antes de suspender", "delay"))
+        .map_err(|e| log::warn!(target: "system", "Não foi possível obter o inibidor do logind: {}", e))
+        .ok()
+}
+
+// XDG_SESSION_ID quando o ambiente gráfico informa; senão a sessão do logind que contém este processo
+fn session_path(manager: &zbus::blocking::Proxy<'_>) -> Result<String, AppError> {
+    let path: zbus::zvariant::OwnedObjectPath = match std::env::var("XDG_SESSION_ID").ok().filter(|id| !id.is_empty()) {
+        Some(id) => manager.call("GetSession", &id),
+        None => manager.call("GetSessionByPID", &std::process::id()),
+    }.map_err(system_error)?;
+    Ok(path.as_str().to_string())
+}
+
+impl SystemEventSource for LogindMonitor {
+    fn next_event(&mut self) -> Result<Option<SystemEvent>, AppError> {
+        for message in self.messages.by_ref() {
+            let message = message.map_err(system_error)?;
+            let at = self.clock.now();
+            let header = message.header();
+            let (Some(path), Some(interface), Some(member)) = (header.path(), header.interface(), header.member()) else {
+                continue;
+            };
+
+            let starting = message.body().deserialize::<bool>().ok();
+            let origin = SignalOrigin { path: path.as_str(), session_path: self.session_path.as_deref() };
+            let Some(kind) = signal_kind(origin, interface.as_str(), member.as_str(), starting) else {
+                continue;
+            };
+
+            // Depois de acordar (ou de um desligamento cancelado) o próximo aviso precisa de um inibidor novo
+            if matches!(kind, SystemEventKind::Wake | SystemEventKind::ShutdownCancelled) && self.inhibitor.is_none() {
+                self.inhibitor = take_inhibitor(&self.manager);
+            }
+            return Ok(Some(SystemEvent { kind, at }));
+        }
+        Ok(None)
+    }
+
+    fn event_handled(&mut self, event: &SystemEvent) {
+        if matches!(event.kind, SystemEventKind::Sleep | SystemEventKind::Shutdown) {
+            // Fechar o descritor libera o logind para seguir com a suspensão ou o desligamento
+            self.inhibitor = None;
+        }
+    }
+}
+
+// Caminho de quem emitiu o sinal e o da sessão deste processo (None quando não foi possível descobrir)
+#[derive(Debug, Clone, Copy)]
+struct SignalOrigin<'a> {
+    path: &'a str,
+    session_path: Option<&'a str>,
+}
+
+// PrepareForSleep e PrepareForShutdown trazem um booleano: true ao começar, false ao voltar/cancelar.
+// Sinais do Manager só no caminho dele; Lock/Unlock só da sessão deste processo
+fn signal_kind(origin: SignalOrigin<'_>, interface: &str, member: &str, starting: Option<bool>) -> Option<SystemEventKind> {
+    let from_manager = origin.path == MANAGER_PATH;
+    let from_own_session = origin.session_path == Some(origin.path);
+
+    match (interface, member, starting) {
+        (MANAGER_INTERFACE, "PrepareForSleep", Some(true)) if from_manager => Some(SystemEventKind::Sleep),
+        (MANAGER_INTERFACE, "PrepareForSleep", Some(false)) if from_manager => Some(SystemEventKind::Wake),
+        (MANAGER_INTERFACE, "PrepareForShutdown", Some(true)) if from_manager => Some(SystemEventKind::Shutdown),
+        (MANAGER_INTERFACE, "PrepareForShutdown", Some(false)) if from_manager => Some(SystemEventKind::ShutdownCancelled),
+        (SESSION_INTERFACE, "Lock", _) if from_own_session => Some(SystemEventKind::Lock),
+        (SESSION_INTERFACE, "Unlock", _) if from_own_session => Some(SystemEventKind::Unlock),
+        _ => None,
+    }
+}
+
+fn system_error(error: impl std::fmt::Display) -> AppError {
+    AppError::System(error.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuspendAction {
+    // Fechar o log e a sessão ativa da tarefa em andamento no instante do evento
+    Suspend { at: DateTime<Utc> },
+    // Todos os motivos foram liberados: retomar a tarefa suspensa no instante do evento
+    Resume { task_id: i64, at: DateTime<Utc> },
+}
+
+// Tampa fechada costuma gerar Lock e depois Sleep: a tarefa só volta quando nenhum dos dois vale mais
+#[derive(Debug, Default)]
+pub struct SuspendTracker {
+    sleeping: bool,
+    locked: bool,
+    shutting_down: bool,
+    suspended_task: Option<i64>,
+}
+
+impl SuspendTracker {
+    pub fn on_event(&mut self, event: &SystemEvent) -> Option<SuspendAction> {
+        let was_held = self.is_held();
+
+        match event.kind {
+            SystemEventKind::Sleep => self.sleeping = true,
+            SystemEventKind::Lock => self.locked = true,
+            SystemEventKind::Shutdown => self.shutting_down = true,
+            SystemEventKind::Wake => self.sleeping = false,
+            SystemEventKind::Unlock => self.locked = false,
+            SystemEventKind::ShutdownCancelled => self.shutting_down = false,
+        }
+
+        match (was_held, self.is_held()) {
+            (false, true) => Some(SuspendAction::Suspend { at: event.at }),
+            (true, false) => self.suspended_task.take().map(|task_id| SuspendAction::Resume { task_id, at: event.at }),
+            _ => None,
+        }
+    }
+
+    pub fn suspended(&mut self, task_id: i64) {
+        self.suspended_task = Some(task_id);
+    }
+
+    // Suspensão ou desligamento avisado e ainda sem volta (bloqueio de tela não conta)
+    pub fn suspend_pending(&self) -> bool {
+        self.sleeping || self.shutting_down
+    }
+
+    fn is_held(&self) -> bool {
+        self.sleeping || self.locked || self.shutting_down
+    }
+}
+
+// Compartilhado com o motor de timer: com uma suspensão pendente as sessões não avançam, senão
+// a noite seria reproduzida como transições antes de o evento de volta ser processado
+#[derive(Debug, Clone, Default)]
+pub struct SuspendPending(Arc<AtomicBool>);
+
+impl SuspendPending {
+    pub fn set(&self, pending: bool) {
+        self.0.store(pending, Ordering::SeqCst);
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+// Mesmo caminho de pause_task, no instante do evento; devolve a tarefa pausada, se havia uma
+pub fn suspend_running_task(conn: &Connection, at: DateTime<Utc>) -> Result<Option<i64>, AppError> {
+    let Some((task_id, segment_started_at)) = find_running_task_segment(conn)? else {
+        return Ok(None);
+    };
+
+    // O evento nunca fica antes do trecho atual da sessão (ex.: transição processada logo depois do sinal)
+    let segment_started_at = DateTime::parse_from_rfc3339(&segment_started_at)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| rusqlite::Error::InvalidColumnType(1, "started_at".to_string(), rusqlite::types::Type::Text))?;
+    let paused_at = at.max(segment_started_at);
+
+    pause_task_at(conn, task_id, &paused_at.to_rfc3339())?;
+    log::info!("Tarefa {} suspensa às {}", task_id, paused_at.to_rfc3339());
+
+    Ok(Some(task_id))
+}
+
+// Retoma só se a tarefa continua como foi deixada; o usuário pode ter mexido nela nesse meio tempo
+pub fn resume_suspended_task(conn: &Connection, task_id: i64, at: DateTime<Utc>) -> Result<bool, AppError> {
+    let status: Option<TaskStatus> = conn.query_row(
+        "SELECT status FROM tasks WHERE id = ?1",
+        [task_id],
+        |row| row.get(0),
+    ).map(Some).or_else(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => Ok(None),
+        e => Err(e),
+    })?;
+
+    if status != Some(TaskStatus::Paused) {
+        log::info!("Tarefa {} não foi retomada após a suspensão (status {:?})", task_id, status);
+        return Ok(false);
+    }
+
+    match resume_task_at(conn, task_id, &at.to_rfc3339()) {
+        Ok(()) => {
+            log::info!("Tarefa {} retomada após a suspensão às {}", task_id, at.to_rfc3339());
+            Ok(true)
+        }
+        Err(AppError::AnotherTaskActive { .. }) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::clock::{Clock, FakeClock};
+    use crate::database::init_in_memory_database;
+    use crate::pomodoro::get_active_pomodoro_session;
+    use crate::tasks::{get_task_by_id, start_task};
+    use crate::time_logs::get_task_remaining_time;
+
+    fn event(kind: SystemEventKind, at: &str) -> SystemEvent {
+        SystemEvent { kind, at: at.parse().unwrap() }
+    }
+
+    // Tarefa de 1 h iniciada às 22:00 com o perfil clássico (25 min de trabalho)
+    fn setup() -> (Connection, FakeClock, i64) {
+        let conn = init_in_memory_database().unwrap();
+        let clock = FakeClock::new("2026-01-01T22:00:00Z".parse().unwrap());
+        conn.execute(
+            "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at)
+             VALUES ('tarefa', 'teste', 1.0, '2026-01-01', 'pending', '2026-01-01T08:00:00+00:00')",
+            [],
+        ).unwrap();
+        let task_id = conn.last_insert_rowid();
+        start_task(&conn, &clock, task_id, None, None).unwrap();
+        (conn, clock, task_id)
+    }
+
+    #[test]
+    fn logind_signals_map_to_events() {
+        let own_session = Some("/org/freedesktop/login1/session/_32");
+        let manager = SignalOrigin { path: MANAGER_PATH, session_path: own_session };
+        let session = SignalOrigin { path: "/org/freedesktop/login1/session/_32", session_path: own_session };
+
+        assert_eq!(signal_kind(manager, MANAGER_INTERFACE, "PrepareForSleep", Some(true)), Some(SystemEventKind::Sleep));
+        assert_eq!(signal_kind(manager, MANAGER_INTERFACE, "PrepareForSleep", Some(false)), Some(SystemEventKind::Wake));
+        assert_eq!(signal_kind(manager, MANAGER_INTERFACE, "PrepareForShutdown", Some(false)), Some(SystemEventKind::ShutdownCancelled));
+        assert_eq!(signal_kind(session, SESSION_INTERFACE, "Lock", None), Some(SystemEventKind::Lock));
+
+        // Sinal sem o booleano esperado ou de outra interface não vira evento
+        assert_eq!(signal_kind(manager, MANAGER_INTERFACE, "PrepareForSleep", None), None);
+        assert_eq!(signal_kind(manager, MANAGER_INTERFACE, "SessionNew", None), None);
+        assert_eq!(signal_kind(session, "org.freedesktop.DBus.Properties", "Lock", None), None);
+    }
+
+    #[test]
+    fn lock_from_another_session_is_ignored() {
+        let own_session = Some("/org/freedesktop/login1/session/_32");
+        let other = SignalOrigin { path: "/org/freedesktop/login1/session/_33", session_path: own_session };
+        assert_eq!(signal_kind(other, SESSION_INTERFACE, "Lock", None), None);
+        assert_eq!(signal_kind(other, SESSION_INTERFACE, "Unlock", None), None);
+
+        // Sessão desconhecida: nenhum bloqueio vale; Manager fora do caminho dele também não
+        let unknown = SignalOrigin { path: "/org/freedesktop/login1/session/_32", session_path: None };
+        assert_eq!(signal_kind(unknown, SESSION_INTERFACE, "Lock", None), None);
+        let misplaced = SignalOrigin { path: "/org/freedesktop/login1/seat/seat0", session_path: own_session };
+        assert_eq!(signal_kind(misplaced, MANAGER_INTERFACE, "PrepareForSleep", Some(true)), None);
+    }
+
+    #[test]
+    fn tracker_resumes_only_after_every_hold_is_released() {
+        let mut tracker = SuspendTracker::default();
+        let lock = event(SystemEventKind::Lock, "2026-01-01T22:10:00Z");
+
+        assert_eq!(tracker.on_event(&lock), Some(SuspendAction::Suspend { at: lock.at }));
+        tracker.suspended(7);
+        assert!(!tracker.suspend_pending());
+        assert_eq!(tracker.on_event(&event(SystemEventKind::Sleep, "2026-01-01T22:10:05Z")), None);
+        assert!(tracker.suspend_pending());
+        assert_eq!(tracker.on_event(&event(SystemEventKind::Wake, "2026-01-02T07:00:00Z")), None);
+        assert!(!tracker.suspend_pending());
+
+        let unlock = event(SystemEventKind::Unlock, "2026-01-02T07:00:30Z");
+        assert_eq!(tracker.on_event(&unlock), Some(SuspendAction::Resume { task_id: 7, at: unlock.at }));
+
+        // Sem tarefa suspensa, liberar não retoma nada
+        let sleep = event(SystemEventKind::Sleep, "2026-01-02T12:00:00Z");
+        assert_eq!(tracker.on_event(&sleep), Some(SuspendAction::Suspend { at: sleep.at }));
+        assert_eq!(tracker.on_event(&event(SystemEventKind::Wake, "2026-01-02T13:00:00Z")), None);
+    }
+
+    #[test]
+    fn night_of_sleep_does_not_count_as_work() {
+        let (conn, clock, task_id) = setup();
+
+        // Tampa fechada às 22:10; o app só processa o sinal depois, já com o relógio adiantado
+        clock.set("2026-01-02T07:00:00Z".parse().unwrap());
+        assert_eq!(suspend_running_task(&conn, "2026-01-01T22:10:00Z".parse().unwrap()), Ok(Some(task_id)));
+        assert_eq!(get_task_by_id(&conn, task_id).unwrap().unwrap().status, TaskStatus::Paused);
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 50 * 60);
+
+        assert_eq!(resume_suspended_task(&conn, task_id, "2026-01-02T07:00:00Z".parse().unwrap()), Ok(true));
+        assert_eq!(get_task_by_id(&conn, task_id).unwrap().unwrap().status, TaskStatus::InProgress);
+
+        // A sessão continua de onde parou: 10 dos 25 minutos já cumpridos
+        let (session, segment_started_at) = get_active_pomodoro_session(&conn, task_id).unwrap().unwrap();
+        assert_eq!((session.session_number, session.elapsed_seconds), (1, 600));
+        assert_eq!(segment_started_at, "2026-01-02T07:00:00+00:00");
+
+        clock.advance(Duration::minutes(5));
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 45 * 60);
+        assert_eq!(suspend_running_task(&conn, clock.now()).unwrap(), Some(task_id));
+        assert_eq!(suspend_running_task(&conn, clock.now()).unwrap(), None);
+    }
+
+    #[test]
+    fn task_changed_by_the_user_is_not_resumed() {
+        let (conn, clock, task_id) = setup();
+        suspend_running_task(&conn, clock.now()).unwrap();
+        crate::tasks::complete_task(&conn, &clock, task_id).unwrap();
+
+        assert_eq!(resume_suspended_task(&conn, task_id, clock.now()), Ok(false));
+        assert_eq!(get_task_by_id(&conn, task_id).unwrap().unwrap().status, TaskStatus::Completed);
+    }
+}
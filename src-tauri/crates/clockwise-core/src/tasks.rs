@@ -0,0 +1,837 @@
+use std::collections::HashMap;
+
+use chrono_tz::Tz;
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::clock::Clock;
+use crate::error::AppError;
+use crate::models::{ActiveSessionInfo, PomodoroSessionInfo, Task, TaskUpdate, TaskWithActiveSession};
+use crate::pomodoro::{
+    create_pomodoro_cycles, finish_running_pomodoro_session, get_next_pomodoro_session, regenerate_pomodoro_sessions,
+    skip_pending_pomodoro_sessions, start_pomodoro_session,
+};
+use crate::profiles::{get_pomodoro_profile, set_task_pomodoro_profile};
+use crate::status::{SessionStatus, SessionType, TaskStatus};
+use crate::time_logs::close_open_time_logs;
+
+pub fn get_task_by_id(conn: &Connection, task_id: i64) -> Result<Option<Task>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT id, name, user, estimated_hours, scheduled_date, status, created_at, started_at, completed_at, profile_id
+         FROM tasks WHERE id = ?1",
+        [task_id],
+        |row| {
+            Ok(Task {
+                id: Some(row.get(0)?),
+                name: row.get(1)?,
+                user: row.get(2)?,
+                estimated_hours: row.get(3)?,
+                scheduled_date: row.get(4)?,
+                status: row.get(5)?,
+                created_at: row.get(6)?,
+                started_at: row.get(7)?,
+                completed_at: row.get(8)?,
+                profile_id: row.get(9)?,
+            })
+        },
+    ).optional()
+}
+
+pub(crate) fn validate_task_update(update: &TaskUpdate) -> Result<(), AppError> {
+    if let Some(name) = &update.name {
+        if name.trim().is_empty() {
+            return Err(AppError::validation("name", "O nome da tarefa não pode ser vazio"));
+        }
+    }
+
+    if let Some(user) = &update.user {
+        if user.trim().is_empty() {
+            return Err(AppError::validation("user", "O usuário da tarefa não pode ser vazio"));
+        }
+    }
+
+    if let Some(estimated_hours) = update.estimated_hours {
+        if !estimated_hours.is_finite() || estimated_hours <= 0.0 {
+            return Err(AppError::validation("estimated_hours", "As horas estimadas devem ser maiores que zero"));
+        }
+    }
+
+    if let Some(scheduled_date) = &update.scheduled_date {
+        chrono::NaiveDate::parse_from_str(scheduled_date, "%Y-%m-%d")
+            .map_err(|_| AppError::validation(
+                "scheduled_date",
+                format!("Data agendada inválida: {} (esperado AAAA-MM-DD)", scheduled_date),
+            ))?;
+    }
+
+    Ok(())
+}
+
+pub fn update_task(conn: &Connection, clock: &dyn Clock, task_id: i64, update: &TaskUpdate) -> Result<Task, AppError> {
+    validate_task_update(update)?;
+
+    let current = get_task_by_id(conn, task_id)?
+        .ok_or(AppError::TaskNotFound { task_id })?;
+
+    conn.execute(
+        "UPDATE tasks SET
+            name = COALESCE(?1, name),
+            user = COALESCE(?2, user),
+            estimated_hours = COALESCE(?3, estimated_hours),
+            scheduled_date = COALESCE(?4, scheduled_date)
+         WHERE id = ?5",
+        rusqlite::params![
+            update.name.as_deref().map(str::trim),
+            update.user.as_deref().map(str::trim),
+            update.estimated_hours,
+            update.scheduled_date,
+            task_id,
+        ],
+    )?;
+
+    let estimate_changed = update.estimated_hours
+        .map(|hours| hours != current.estimated_hours)
+        .unwrap_or(false);
+
+    // Tarefas concluídas não têm mais sessões a executar
+    if estimate_changed && current.status != TaskStatus::Completed {
+        regenerate_pomodoro_sessions(conn, task_id, &clock.now().to_rfc3339())?;
+    }
+
+    get_task_by_id(conn, task_id)?
+        .ok_or(AppError::TaskNotFound { task_id })
+}
+
+// Toda mudança de status da tarefa passa por aqui para respeitar o ciclo de vida
+pub(crate) fn set_task_status(conn: &Connection, task_id: i64, next: TaskStatus, now: &str) -> Result<TaskStatus, AppError> {
+    let current: TaskStatus = conn.query_row(
+        "SELECT status FROM tasks WHERE id = ?1",
+        [task_id],
+        |row| row.get(0),
+    ).optional()?
+    .ok_or(AppError::TaskNotFound { task_id })?;
+    let next = current.transition(next)?;
+
+    if next == TaskStatus::Completed {
+        conn.execute(
+            "UPDATE tasks SET status = ?1, completed_at = ?2 WHERE id = ?3",
+            rusqlite::params![next, now, task_id],
+        )?;
+    } else {
+        conn.execute(
+            "UPDATE tasks SET status = ?1 WHERE id = ?2",
+            rusqlite::params![next, task_id],
+        )?;
+    }
+
+    Ok(next)
+}
+
+// Tarefa (diferente da informada) que está em trabalho ou pausa curta, se houver
+pub(crate) fn find_other_running_task(conn: &Connection, task_id: i64) -> Result<Option<i64>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT id FROM tasks WHERE status IN (?1, ?2) AND id != ?3 ORDER BY id LIMIT 1",
+        rusqlite::params![TaskStatus::InProgress, TaskStatus::Waiting, task_id],
+        |row| row.get(0),
+    ).optional()
+}
+
+// Tarefa em andamento (trabalho ou pausa do Pomodoro) e o início do trecho atual da sessão
+pub(crate) fn find_running_task_segment(conn: &Connection) -> Result<Option<(i64, String)>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT t.id, a.started_at
+         FROM tasks t
+         JOIN active_sessions a ON a.task_id = t.id
+         WHERE t.status IN (?1, ?2)
+         ORDER BY t.id LIMIT 1",
+        rusqlite::params![TaskStatus::InProgress, TaskStatus::Waiting],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional()
+}
+
+pub fn start_task(conn: &Connection, clock: &dyn Clock, task_id: i64, stop_and_start: Option<bool>, profile_id: Option<i64>) -> Result<(), AppError> {
+    let now = clock.now().to_rfc3339();
+    log::debug!("Iniciando tarefa: {}", task_id);
+    // Já ativa: em trabalho (log aberto) ou em pausa do Pomodoro, que não tem log aberto mas tem sessão ativa
+    let (status, has_active_session, open_logs): (TaskStatus, bool, i64) = conn.query_row(
+        "SELECT t.status,
+                EXISTS (SELECT 1 FROM active_sessions a WHERE a.task_id = t.id),
+                (SELECT COUNT(*) FROM task_time_logs l WHERE l.task_id = t.id AND l.ended_at IS NULL)
+         FROM tasks t WHERE t.id = ?1",
+        [task_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).optional()?
+    .ok_or(AppError::TaskNotFound { task_id })?;
+
+    if status.is_running() || has_active_session || open_logs > 0 {
+        return Err(AppError::TaskAlreadyActive { task_id });
+    }
+
+    // NOVA REGRA: Verificar se há alguma outra tarefa em andamento
+    let running_task_id = find_other_running_task(conn, task_id)?;
+
+    // Se stop_and_start for true, pausar automaticamente tarefas ativas
+    if let Some(running_task_id) = running_task_id {
+        if stop_and_start.unwrap_or(false) {
+            log::debug!("stop_and_start=true: pausando tarefas ativas automaticamente");
+
+            // Buscar IDs das tarefas ativas
+            let mut stmt = conn.prepare(
+                "SELECT id FROM tasks WHERE status IN (?1, ?2) AND id != ?3"
+            )?;
+
+            let active_task_ids: Vec<i64> = stmt.query_map(rusqlite::params![TaskStatus::InProgress, TaskStatus::Waiting, task_id], |row| {
+                row.get::<_, i64>(0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+            // Pausar cada tarefa ativa
+            for active_task_id in active_task_ids {
+                log::debug!("Pausando tarefa ativa: {}", active_task_id);
+
+                // Guardar o progresso da sessão antes de remover a sessão ativa
+                finish_running_pomodoro_session(conn, active_task_id, SessionStatus::Interrupted, &now)?;
+
+                // Remover sessão ativa
+                conn.execute(
+                    "DELETE FROM active_sessions WHERE task_id = ?1",
+                    [active_task_id],
+                )?;
+
+                // Finalizar log de tempo se existir
+                conn.execute(
+                    "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
+                    [&now, &active_task_id.to_string()],
+                )?;
+
+                set_task_status(conn, active_task_id, TaskStatus::Paused, &now)?;
+
+                log::info!("Tarefa {} pausada automaticamente", active_task_id);
+            }
+        } else {
+            return Err(AppError::AnotherTaskActive { task_id, active_task_id: running_task_id });
+        }
+    }
+
+    // Perfil informado no início sobrescreve o da tarefa para as sessões ainda pendentes
+    if let Some(profile_id) = profile_id {
+        set_task_pomodoro_profile(conn, task_id, Some(profile_id), &now)?;
+    }
+
+    // Buscar próxima sessão Pomodoro
+    let next_session = get_next_pomodoro_session(conn, task_id, &now)?;
+
+        match &next_session {
+        Some(pomodoro_session) => {
+            // Iniciar sessão Pomodoro
+            let status = start_pomodoro_session(conn, task_id, pomodoro_session, &now)?;
+
+            // Atualizar started_at apenas se for a primeira vez
+            let mut stmt = conn.prepare("SELECT started_at FROM tasks WHERE id = ?1")?;
+            let current_started_at: Option<String> = stmt.query_row([task_id], |row| row.get(0))?;
+
+            if current_started_at.is_none() {
+                conn.execute(
+                    "UPDATE tasks SET started_at = ?1 WHERE id = ?2",
+                    [&now, &task_id.to_string()],
+                )?;
+            }
+
+            log::info!("Tarefa {} iniciada com sessão Pomodoro: {} ({})",
+                task_id, pomodoro_session.session_type, status);
+        }
+        None => {
+            // Não há mais sessões Pomodoro, marcar como completada
+            set_task_status(conn, task_id, TaskStatus::Completed, &now)?;
+
+            log::info!("Tarefa {} completada - todos os ciclos Pomodoro finalizados", task_id);
+        }
+    }
+
+    // Criar novo log de tempo (apenas para sessões de trabalho)
+    if let Some(session) = &next_session {
+        if session.session_type == SessionType::Work {
+            conn.execute(
+                "INSERT INTO task_time_logs (task_id, started_at) VALUES (?1, ?2)",
+                [&task_id.to_string(), &now],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn pause_task(conn: &Connection, clock: &dyn Clock, task_id: i64) -> Result<(), AppError> {
+    pause_task_at(conn, task_id, &clock.now().to_rfc3339())
+}
+
+// Pausa num instante que pode estar no passado (ex.: quando o usuário parou de mexer no computador)
+pub(crate) fn pause_task_at(conn: &Connection, task_id: i64, now: &str) -> Result<(), AppError> {
+    // Verificar se há sessão ativa
+    let active_session_exists: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM active_sessions WHERE task_id = ?1",
+        [task_id],
+        |row| row.get(0),
+    )?;
+
+    if active_session_exists == 0 {
+        return Err(AppError::NoActiveSession { task_id });
+    }
+
+    // Finalizar log de tempo ANTES de remover sessão ativa (para evitar conflito com check_and_advance)
+    let rows_updated = close_open_time_logs(conn, task_id, now)?;
+
+    log::debug!("Pausando tarefa {} às {} - {} logs finalizados", task_id, now, rows_updated);
+
+    // Guardar o tempo já cumprido; a sessão será retomada do ponto em que parou
+    finish_running_pomodoro_session(conn, task_id, SessionStatus::Interrupted, now)?;
+
+    // Remover sessão ativa (pausa o Pomodoro) - fazer isso por último
+    conn.execute(
+        "DELETE FROM active_sessions WHERE task_id = ?1",
+        [task_id],
+    )?;
+
+    // Atualizar status da tarefa para 'paused'
+    set_task_status(conn, task_id, TaskStatus::Paused, now)?;
+
+    log::info!("Tarefa {} pausada - sessão Pomodoro interrompida", task_id);
+    Ok(())
+}
+
+pub fn resume_task(conn: &Connection, clock: &dyn Clock, task_id: i64) -> Result<(), AppError> {
+    resume_task_at(conn, task_id, &clock.now().to_rfc3339())
+}
+
+pub(crate) fn resume_task_at(conn: &Connection, task_id: i64, now: &str) -> Result<(), AppError> {
+    // Verificar se a tarefa existe e está pausada
+    let mut stmt = conn.prepare(
+        "SELECT status FROM tasks WHERE id = ?1"
+    )?;
+
+    let status: TaskStatus = stmt.query_row([task_id], |row| row.get(0))
+        .map_err(|_| AppError::TaskNotFound { task_id })?;
+
+    // Tarefa concluída tem erro próprio; os demais status apenas não estão pausados
+    status.transition(TaskStatus::InProgress)?;
+    if status != TaskStatus::Paused {
+        return Err(AppError::TaskNotPaused { task_id, status });
+    }
+
+    // NOVA REGRA: Verificar se há alguma outra tarefa em andamento
+    if let Some(running_task_id) = find_other_running_task(conn, task_id)? {
+        return Err(AppError::AnotherTaskActive { task_id, active_task_id: running_task_id });
+    }
+
+    // Verificar se não há sessão ativa
+    let active_session_exists: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM active_sessions WHERE task_id = ?1",
+        [task_id],
+        |row| row.get(0),
+    )?;
+
+    if active_session_exists > 0 {
+        return Err(AppError::SessionAlreadyActive { task_id });
+    }
+
+    // Buscar próxima sessão Pomodoro (a mesma lógica de start_task)
+    let next_session = get_next_pomodoro_session(conn, task_id, now)?;
+
+    match next_session {
+        Some(pomodoro_session) => {
+            // Retomar com próxima sessão Pomodoro
+            let status = start_pomodoro_session(conn, task_id, &pomodoro_session, now)?;
+
+            // Criar novo log de tempo apenas para sessões de trabalho
+            if pomodoro_session.session_type == SessionType::Work {
+                conn.execute(
+                    "INSERT INTO task_time_logs (task_id, started_at) VALUES (?1, ?2)",
+                    rusqlite::params![task_id, now],
+                )?;
+                log::debug!("Retomando tarefa {} às {} - novo log criado", task_id, now);
+            }
+
+            log::info!("Tarefa {} retomada com sessão Pomodoro: {} ({})",
+                task_id, pomodoro_session.session_type, status);
+        }
+        None => {
+            // Não há mais sessões, completar tarefa
+            set_task_status(conn, task_id, TaskStatus::Completed, now)?;
+
+            log::info!("Tarefa {} completada ao retomar - todos os ciclos Pomodoro finalizados", task_id);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn complete_task(conn: &Connection, clock: &dyn Clock, task_id: i64) -> Result<(), AppError> {
+    let now = clock.now().to_rfc3339();
+
+    // Sessão em andamento foi interrompida e as restantes não serão mais executadas
+    finish_running_pomodoro_session(conn, task_id, SessionStatus::Interrupted, &now)?;
+    skip_pending_pomodoro_sessions(conn, task_id)?;
+
+    // Remover sessão ativa se existir
+    conn.execute(
+        "DELETE FROM active_sessions WHERE task_id = ?1",
+        [task_id],
+    )?;
+
+    // Finalizar log ativo se existir
+    conn.execute(
+        "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
+        [&now, &task_id.to_string()],
+    )?;
+
+    // Atualizar status da tarefa
+    set_task_status(conn, task_id, TaskStatus::Completed, &now)?;
+
+    log::info!("Tarefa {} completada manualmente", task_id);
+    Ok(())
+}
+
+pub fn add_task(
+    conn: &Connection,
+    clock: &dyn Clock,
+    name: String,
+    user: String,
+    estimated_hours: f64,
+    scheduled_date: String,
+    profile_id: Option<i64>,
+) -> Result<Task, AppError> {
+    let now = clock.now().to_rfc3339();
+
+    if let Some(profile_id) = profile_id {
+        get_pomodoro_profile(conn, profile_id)?
+            .ok_or(AppError::ProfileNotFound { profile_id })?;
+    }
+
+    conn.execute(
+        "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at, profile_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![name, user, estimated_hours, scheduled_date, TaskStatus::Pending, now, profile_id],
+    )?;
+
+    let id = conn.last_insert_rowid();
+
+    // Criar sessões Pomodoro automaticamente quando a tarefa é criada
+    create_pomodoro_cycles(conn, id, &now)?;
+    log::info!("Sessões Pomodoro criadas automaticamente para tarefa {}", id);
+
+    Ok(Task {
+        id: Some(id),
+        name,
+        user,
+        estimated_hours,
+        scheduled_date,
+        status: TaskStatus::Pending,
+        created_at: now,
+        started_at: None,
+        completed_at: None,
+        profile_id,
+    })
+}
+
+pub fn delete_task(conn: &Connection, task_id: i64) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM tasks WHERE id = ?1",
+        [&task_id.to_string()],
+    )?;
+
+    Ok(())
+}
+
+pub fn load_tasks(conn: &Connection) -> Result<Vec<Task>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, user, estimated_hours, scheduled_date, status, created_at, started_at, completed_at, profile_id
+         FROM tasks ORDER BY scheduled_date ASC, created_at ASC"
+    )?;
+
+    let task_iter = stmt.query_map([], |row| {
+        Ok(Task {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            user: row.get(2)?,
+            estimated_hours: row.get(3)?,
+            scheduled_date: row.get(4)?,
+            status: row.get(5)?,
+            created_at: row.get(6)?,
+            started_at: row.get(7)?,
+            completed_at: row.get(8)?,
+            profile_id: row.get(9)?,
+        })
+    })?;
+
+    let mut tasks = Vec::new();
+    for task in task_iter {
+        tasks.push(task?);
+    }
+
+    Ok(tasks)
+}
+
+pub fn get_today_tasks(conn: &Connection, clock: &dyn Clock, tz: Tz) -> Result<Vec<Task>, AppError> {
+    let today = clock.today(tz).format("%Y-%m-%d").to_string();
+
+    let mut stmt = conn.prepare(
+        "SELECT id, name, user, estimated_hours, scheduled_date, status, created_at, started_at, completed_at, profile_id
+         FROM tasks WHERE scheduled_date = ?1 ORDER BY created_at ASC"
+    )?;
+
+    let task_iter = stmt.query_map([&today], |row| {
+        Ok(Task {
+            id: Some(row.get(0)?),
+            name: row.get(1)?,
+            user: row.get(2)?,
+            estimated_hours: row.get(3)?,
+            scheduled_date: row.get(4)?,
+            status: row.get(5)?,
+            created_at: row.get(6)?,
+            started_at: row.get(7)?,
+            completed_at: row.get(8)?,
+            profile_id: row.get(9)?,
+        })
+    })?;
+
+    let mut tasks = Vec::new();
+    for task in task_iter {
+        tasks.push(task?);
+    }
+
+    Ok(tasks)
+}
+
+// Página da lista (ativas primeiro, depois por data); LIMIT -1 no SQLite significa "sem limite".
+// O id no fim da ordenação deixa as páginas estáveis entre chamadas. ?3 e ?4 são os status em andamento.
+const TASK_PAGE_SQL: &str =
+    "SELECT t.id FROM tasks t
+     ORDER BY
+        CASE
+            WHEN t.status IN (?3, ?4) THEN 0
+            ELSE 1
+        END ASC,
+        t.scheduled_date ASC,
+        t.created_at ASC,
+        t.id ASC
+     LIMIT ?1 OFFSET ?2";
+
+// Duas consultas por chamada, independente do número de tarefas: a página com a sessão ativa
+// e, de uma vez, todas as sessões Pomodoro das tarefas dessa página
+pub fn load_tasks_with_sessions(conn: &Connection, limit: Option<u32>, offset: u32) -> Result<Vec<TaskWithActiveSession>, AppError> {
+    let limit = limit.map_or(-1, i64::from);
+
+    let mut stmt = conn.prepare(&format!(
+        "WITH page AS ({TASK_PAGE_SQL})
+         SELECT t.id, t.name, t.user, t.estimated_hours, t.scheduled_date, t.status,
+                t.created_at, t.started_at, t.completed_at,
+                a.started_at as session_started_at, p.session_type, p.duration_seconds, t.profile_id,
+                p.elapsed_seconds
+         FROM page
+         JOIN tasks t ON t.id = page.id
+         LEFT JOIN active_sessions a ON t.id = a.task_id
+         LEFT JOIN pomodoro_sessions p ON a.pomodoro_id = p.id
+         ORDER BY
+            CASE
+                WHEN t.status IN (?3, ?4) THEN 0
+                ELSE 1
+            END ASC,
+            t.scheduled_date ASC,
+            t.created_at ASC,
+            t.id ASC"
+    ))?;
+
+    let page_params = rusqlite::params![limit, i64::from(offset), TaskStatus::InProgress, TaskStatus::Waiting];
+    let task_iter = stmt.query_map(page_params, |row| {
+        let task_id: i64 = row.get(0)?;
+        let session_started_at: Option<String> = row.get(9)?;
+        let session_type: Option<SessionType> = row.get(10)?;
+        let duration_seconds: Option<i32> = row.get(11)?;
+        let elapsed_seconds: Option<i32> = row.get(13)?;
+
+        let active_session = if let (Some(started_at), Some(s_type), Some(duration)) =
+            (session_started_at, session_type, duration_seconds) {
+
+            let started_time = chrono::DateTime::parse_from_rfc3339(&started_at)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(9, "session_started_at".to_string(), rusqlite::types::Type::Text))?;
+            // Sessão retomada após pausa termina só com o que faltava cumprir
+            let elapsed = elapsed_seconds.unwrap_or(0);
+            let ends_at = started_time + chrono::Duration::seconds((duration - elapsed).max(0) as i64);
+
+            Some(ActiveSessionInfo {
+                session_type: s_type,
+                started_at,
+                ends_at: ends_at.to_rfc3339(),
+                duration_seconds: duration,
+                elapsed_seconds: elapsed,
+            })
+        } else {
+            None
+        };
+
+        Ok(TaskWithActiveSession {
+            id: Some(task_id),
+            name: row.get(1)?,
+            user: row.get(2)?,
+            estimated_hours: row.get(3)?,
+            scheduled_date: row.get(4)?,
+            status: row.get(5)?,
+            created_at: row.get(6)?,
+            started_at: row.get(7)?,
+            completed_at: row.get(8)?,
+            profile_id: row.get(12)?,
+            active_session,
+            pomodoro_sessions: Vec::new(), // Será preenchido depois
+        })
+    })?;
+
+    let mut tasks = task_iter.collect::<Result<Vec<_>, _>>()?;
+
+    // Posição de cada tarefa na página, para distribuir as sessões sem nova consulta
+    let positions: HashMap<i64, usize> = tasks.iter().enumerate()
+        .filter_map(|(index, task)| task.id.map(|id| (id, index)))
+        .collect();
+
+    let mut pomodoro_stmt = conn.prepare(&format!(
+        "WITH page AS ({TASK_PAGE_SQL})
+         SELECT ps.task_id, ps.id, ps.session_number, ps.session_type, ps.duration_seconds, ps.created_at,
+                a.started_at as active_started_at, ps.status, ps.started_at, ps.ended_at,
+                ps.elapsed_seconds
+         FROM page
+         JOIN pomodoro_sessions ps ON ps.task_id = page.id
+         LEFT JOIN active_sessions a ON ps.id = a.pomodoro_id AND ps.task_id = a.task_id
+         ORDER BY ps.task_id ASC, ps.session_number ASC"
+    ))?;
+
+    let mut rows = pomodoro_stmt.query(page_params)?;
+    while let Some(row) = rows.next()? {
+        let task_id: i64 = row.get(0)?;
+        let active_started_at: Option<String> = row.get(6)?;
+        let session_started_at: Option<String> = row.get(8)?;
+        let session = PomodoroSessionInfo {
+            id: Some(row.get(1)?),
+            session_number: row.get(2)?,
+            session_type: row.get(3)?,
+            duration_seconds: row.get(4)?,
+            created_at: row.get(5)?,
+            is_active: active_started_at.is_some(),
+            status: row.get(7)?,
+            started_at: active_started_at.or(session_started_at),
+            ended_at: row.get(9)?,
+            elapsed_seconds: row.get(10)?,
+        };
+
+        if let Some(&index) = positions.get(&task_id) {
+            tasks[index].pomodoro_sessions.push(session);
+        }
+    }
+
+    log::trace!("{} tarefas carregadas com sessões (limite {}, início {})", tasks.len(), limit, offset);
+
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+    use crate::database::init_in_memory_database;
+    use crate::pomodoro::{advance_pomodoro_sessions, check_and_advance_pomodoro_sessions, get_active_pomodoro_session};
+    use crate::settings::OfflinePolicy;
+    use crate::time_logs::get_task_remaining_time;
+
+    fn open_test_database() -> Connection {
+        init_in_memory_database().unwrap()
+    }
+
+    // Relógio fixo: os testes avançam o tempo em vez de esperar
+    fn test_clock() -> FakeClock {
+        FakeClock::new("2026-01-01T09:00:00Z".parse().unwrap())
+    }
+
+    fn insert_task(conn: &Connection, name: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at)
+             VALUES (?1, 'teste', 1.0, '2026-01-01', 'pending', ?2)",
+            [name, &test_clock().now().to_rfc3339()],
+        ).unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn task_status(conn: &Connection, task_id: i64) -> TaskStatus {
+        conn.query_row("SELECT status FROM tasks WHERE id = ?1", [task_id], |row| row.get(0)).unwrap()
+    }
+
+    // Faz qualquer escrita de log de tempo da tarefa falhar no meio do comando
+    fn fail_time_logs_for(conn: &Connection, task_id: i64) {
+        conn.execute_batch(&format!(
+            "CREATE TRIGGER fail_time_logs BEFORE INSERT ON task_time_logs
+             WHEN NEW.task_id = {}
+             BEGIN SELECT RAISE(ABORT, 'falha simulada'); END;",
+            task_id
+        )).unwrap();
+    }
+
+    #[test]
+    fn task_status_transitions_follow_lifecycle() {
+        use TaskStatus::*;
+
+        assert_eq!(Pending.transition(InProgress), Ok(InProgress));
+        assert_eq!(InProgress.transition(Waiting), Ok(Waiting));
+        assert_eq!(Waiting.transition(Paused), Ok(Paused));
+        assert_eq!(Paused.transition(Completed), Ok(Completed));
+        assert_eq!(InProgress.transition(InProgress), Ok(InProgress));
+
+        assert!(Pending.transition(Paused).is_err());
+        assert!(Paused.transition(Pending).is_err());
+        assert!(Completed.transition(InProgress).is_err());
+        assert!(Completed.transition(Completed).is_err());
+    }
+
+    #[test]
+    fn resuming_completed_task_is_rejected() {
+        let conn = open_test_database();
+        let clock = test_clock();
+        let task_id = insert_task(&conn, "tarefa");
+        start_task(&conn, &clock, task_id, None, None).unwrap();
+        complete_task(&conn, &clock, task_id).unwrap();
+
+        assert_eq!(
+            resume_task(&conn, &clock, task_id),
+            Err(AppError::InvalidTransition { from: TaskStatus::Completed, to: TaskStatus::InProgress })
+        );
+        assert_eq!(
+            complete_task(&conn, &clock, task_id),
+            Err(AppError::InvalidTransition { from: TaskStatus::Completed, to: TaskStatus::Completed })
+        );
+        assert_eq!(task_status(&conn, task_id), TaskStatus::Completed);
+    }
+
+    #[test]
+    fn start_task_reports_conflicting_task() {
+        let conn = open_test_database();
+        let clock = test_clock();
+        let first = insert_task(&conn, "primeira");
+        let second = insert_task(&conn, "segunda");
+        start_task(&conn, &clock, first, None, None).unwrap();
+
+        let error = start_task(&conn, &clock, second, Some(false), None).unwrap_err();
+        assert_eq!(error, AppError::AnotherTaskActive { task_id: second, active_task_id: first });
+
+        let payload = serde_json::to_value(&error).unwrap();
+        assert_eq!(payload["code"], "AnotherTaskActive");
+        assert_eq!(payload["details"]["active_task_id"], first);
+        assert!(payload["message"].as_str().unwrap().starts_with("Apenas uma tarefa"));
+    }
+
+    #[test]
+    fn start_task_rolls_back_stop_and_start_on_failure() {
+        let mut conn = open_test_database();
+        let clock = test_clock();
+        let first = insert_task(&conn, "primeira");
+        let second = insert_task(&conn, "segunda");
+        start_task(&conn, &clock, first, None, None).unwrap();
+
+        fail_time_logs_for(&conn, second);
+
+        let tx = conn.transaction().unwrap();
+        assert!(start_task(&tx, &clock, second, Some(true), None).is_err());
+        drop(tx);
+
+        // A primeira tarefa não pode ter sido pausada pela tentativa que falhou
+        assert_eq!(task_status(&conn, first), TaskStatus::InProgress);
+        assert!(get_active_pomodoro_session(&conn, first).unwrap().is_some());
+        let open_logs: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM task_time_logs WHERE task_id = ?1 AND ended_at IS NULL",
+            [first],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(open_logs, 1);
+
+        assert_eq!(task_status(&conn, second), TaskStatus::Pending);
+        let second_sessions: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM pomodoro_sessions WHERE task_id = ?1",
+            [second],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(second_sessions, 0);
+    }
+
+    #[test]
+    fn advance_rolls_back_task_on_failure() {
+        let conn = open_test_database();
+        let clock = test_clock();
+        let task_id = insert_task(&conn, "tarefa");
+        start_task(&conn, &clock, task_id, None, None).unwrap();
+
+        // Trabalho + pausa inteiros já passaram: a próxima sessão de trabalho abriria um log
+        clock.advance(chrono::Duration::seconds(1500 + 300 + 60));
+        fail_time_logs_for(&conn, task_id);
+
+        assert!(advance_pomodoro_sessions(&conn, clock.now(), OfflinePolicy::Replay).is_err());
+
+        let (session, _) = get_active_pomodoro_session(&conn, task_id).unwrap().unwrap();
+        assert_eq!(session.session_number, 1);
+        assert_eq!(session.status, SessionStatus::Running);
+        let completed: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM pomodoro_sessions WHERE task_id = ?1 AND status = 'completed'",
+            [task_id],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(completed, 0);
+    }
+
+    #[test]
+    fn work_session_ends_after_twenty_five_minutes() {
+        let conn = open_test_database();
+        let clock = test_clock();
+        let task_id = insert_task(&conn, "tarefa");
+        start_task(&conn, &clock, task_id, None, None).unwrap();
+
+        clock.advance(chrono::Duration::minutes(25) - chrono::Duration::seconds(1));
+        assert!(check_and_advance_pomodoro_sessions(&conn, &clock).unwrap().is_empty());
+        assert_eq!(task_status(&conn, task_id), TaskStatus::InProgress);
+
+        clock.advance(chrono::Duration::seconds(1));
+        assert_eq!(check_and_advance_pomodoro_sessions(&conn, &clock).unwrap(), vec![task_id]);
+        assert_eq!(task_status(&conn, task_id), TaskStatus::Waiting);
+
+        // A pausa não consome a estimativa: restam 35 minutos da hora estimada
+        clock.advance(chrono::Duration::minutes(2));
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 35 * 60);
+    }
+
+    #[test]
+    fn tasks_with_sessions_are_paginated_with_active_first() {
+        let conn = open_test_database();
+        let clock = test_clock();
+        let ids: Vec<i64> = ["primeira", "segunda", "terceira"].iter()
+            .map(|name| add_task(&conn, &clock, name.to_string(), "teste".to_string(), 1.0, "2026-01-01".to_string(), None)
+                .unwrap().id.unwrap())
+            .collect();
+        start_task(&conn, &clock, ids[2], None, None).unwrap();
+
+        let all = load_tasks_with_sessions(&conn, None, 0).unwrap();
+        assert_eq!(all.iter().map(|t| t.id.unwrap()).collect::<Vec<_>>(), vec![ids[2], ids[0], ids[1]]);
+
+        let first_page = load_tasks_with_sessions(&conn, Some(2), 0).unwrap();
+        let second_page = load_tasks_with_sessions(&conn, Some(2), 2).unwrap();
+        assert_eq!(first_page.iter().map(|t| t.id.unwrap()).collect::<Vec<_>>(), vec![ids[2], ids[0]]);
+        assert_eq!(second_page.iter().map(|t| t.id.unwrap()).collect::<Vec<_>>(), vec![ids[1]]);
+
+        // Cada tarefa recebe só as próprias sessões, em ordem
+        let running = &first_page[0];
+        assert!(running.active_session.is_some());
+        let numbers: Vec<i32> = running.pomodoro_sessions.iter().map(|s| s.session_number).collect();
+        assert_eq!(numbers, (1..=numbers.len() as i32).collect::<Vec<_>>());
+        assert!(running.pomodoro_sessions[0].is_active);
+
+        for task in all.iter().skip(1) {
+            assert!(task.active_session.is_none());
+            assert!(task.pomodoro_sessions.iter().all(|s| !s.is_active));
+            let expected: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM pomodoro_sessions WHERE task_id = ?1", [task.id.unwrap()], |row| row.get(0),
+            ).unwrap();
+            assert_eq!(task.pomodoro_sessions.len() as i64, expected);
+        }
+    }
+}
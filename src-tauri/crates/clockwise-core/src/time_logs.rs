@@ -0,0 +1,463 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::clock::Clock;
+use crate::error::AppError;
+use crate::models::{DailyTimeTotal, TaskTimeLog};
+
+// Fecha os logs abertos da tarefa no instante informado; devolve quantos foram fechados
+pub(crate) fn close_open_time_logs(conn: &Connection, task_id: i64, ended_at: &str) -> Result<usize, rusqlite::Error> {
+    conn.execute(
+        "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
+        rusqlite::params![ended_at, task_id],
+    )
+}
+
+pub fn calculate_task_remaining_time(conn: &Connection, clock: &dyn Clock, task_id: i64, estimated_hours: f64) -> Result<i64, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT started_at, ended_at FROM task_time_logs WHERE task_id = ?1 ORDER BY started_at"
+    )?;
+
+    let log_iter = stmt.query_map([task_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?, // started_at
+            row.get::<_, Option<String>>(1)?, // ended_at
+        ))
+    })?;
+
+    let mut total_seconds_worked = 0i64;
+    let now = clock.now();
+
+    log::debug!("Calculando tempo para tarefa {}: estimated_hours = {}", task_id, estimated_hours);
+
+    for log_result in log_iter {
+        let (started_at_str, ended_at_opt) = log_result?;
+
+        let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "started_at".to_string(), rusqlite::types::Type::Text))?;
+
+        let ended_at = match ended_at_opt {
+            Some(ended_at_str) => {
+                let ended_time = chrono::DateTime::parse_from_rfc3339(&ended_at_str)
+                    .map_err(|_| rusqlite::Error::InvalidColumnType(1, "ended_at".to_string(), rusqlite::types::Type::Text))?;
+                log::trace!("Log completo: {} → {} (finalizado)", started_at_str, ended_at_str);
+                ended_time
+            }
+            None => {
+                log::trace!("Log ativo: {} → agora (em andamento)", started_at_str);
+                now.into() // Se não tem ended_at, significa que está ativo, usa tempo atual
+            }
+        };
+
+        let duration = ended_at.signed_duration_since(started_at);
+        let duration_seconds = duration.num_seconds();
+        total_seconds_worked += duration_seconds;
+
+        log::trace!("Duração deste período: {}s", duration_seconds);
+    }
+
+    let estimated_seconds = (estimated_hours * 3600.0) as i64;
+    let remaining_seconds = estimated_seconds - total_seconds_worked;
+
+    log::debug!("Total trabalhado: {}s, Estimado: {}s, Restante: {}s",
+             total_seconds_worked, estimated_seconds, remaining_seconds);
+
+    Ok(remaining_seconds)
+}
+
+pub fn get_task_remaining_time(conn: &Connection, clock: &dyn Clock, task_id: i64) -> Result<i64, AppError> {
+    // Buscar estimated_hours da tarefa
+    let mut stmt = conn.prepare(
+        "SELECT estimated_hours FROM tasks WHERE id = ?1"
+    )?;
+
+    let estimated_hours: f64 = stmt.query_row([task_id], |row| row.get(0))
+        .map_err(|_| AppError::TaskNotFound { task_id })?;
+
+    // Calcular tempo restante
+    let remaining_seconds = calculate_task_remaining_time(conn, clock, task_id, estimated_hours)?;
+
+    Ok(remaining_seconds)
+}
+
+fn row_to_time_log(row: &rusqlite::Row) -> Result<TaskTimeLog, rusqlite::Error> {
+    Ok(TaskTimeLog {
+        id: Some(row.get(0)?),
+        task_id: row.get(1)?,
+        started_at: row.get(2)?,
+        ended_at: row.get(3)?,
+    })
+}
+
+fn get_time_log(conn: &Connection, log_id: i64) -> Result<TaskTimeLog, AppError> {
+    conn.query_row(
+        "SELECT id, task_id, started_at, ended_at FROM task_time_logs WHERE id = ?1",
+        [log_id],
+        row_to_time_log,
+    ).optional()?
+    .ok_or(AppError::TimeLogNotFound { log_id })
+}
+
+fn ensure_task_exists(conn: &Connection, task_id: i64) -> Result<(), AppError> {
+    conn.query_row("SELECT id FROM tasks WHERE id = ?1", [task_id], |row| row.get::<_, i64>(0))
+        .optional()?
+        .ok_or(AppError::TaskNotFound { task_id })?;
+    Ok(())
+}
+
+fn parse_log_time(field: &'static str, value: &str) -> Result<DateTime<Utc>, AppError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| AppError::validation(field, "Data e hora devem estar no formato RFC3339"))
+}
+
+// Valida um período fechado e devolve as datas normalizadas em UTC, como as gravadas pelo timer
+fn validate_time_log(
+    conn: &Connection,
+    clock: &dyn Clock,
+    task_id: i64,
+    log_id: Option<i64>,
+    started_at: &str,
+    ended_at: &str,
+) -> Result<(String, String), AppError> {
+    let started = parse_log_time("started_at", started_at)?;
+    let ended = parse_log_time("ended_at", ended_at)?;
+    let now = clock.now();
+
+    if ended <= started {
+        return Err(AppError::validation("ended_at", "O fim do registro deve ser depois do início"));
+    }
+
+    if ended > now {
+        return Err(AppError::validation("ended_at", "O registro não pode terminar no futuro"));
+    }
+
+    // Só uma tarefa por vez, como no start_task: o período não pode cruzar registros de nenhuma tarefa,
+    // senão o mesmo tempo contaria duas vezes nos totais por dia. Logs abertos contam até agora.
+    // O filtro em texto é largo (sufixos de fuso variam); a comparação exata é feita abaixo
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, started_at, ended_at FROM task_time_logs
+         WHERE started_at < ?2 AND (ended_at IS NULL OR ended_at > ?1)
+         ORDER BY started_at"
+    )?;
+    let others = stmt.query_map(
+        [(started - Duration::days(1)).to_rfc3339(), (ended + Duration::days(1)).to_rfc3339()],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, Option<String>>(3)?)),
+    )?;
+
+    for other in others {
+        let (other_id, other_task_id, other_started_at, other_ended_at) = other?;
+        if Some(other_id) == log_id {
+            continue;
+        }
+
+        let other_started = parse_log_time("started_at", &other_started_at)?;
+        let other_ended = match other_ended_at {
+            Some(value) => parse_log_time("ended_at", &value)?,
+            None => now,
+        };
+
+        if started < other_ended && other_started < ended {
+            return Err(AppError::TimeLogOverlap { task_id, conflicting_log_id: other_id, conflicting_task_id: other_task_id });
+        }
+    }
+
+    Ok((started.to_rfc3339(), ended.to_rfc3339()))
+}
+
+pub fn list_task_time_logs(conn: &Connection, task_id: i64) -> Result<Vec<TaskTimeLog>, AppError> {
+    ensure_task_exists(conn, task_id)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, task_id, started_at, ended_at FROM task_time_logs WHERE task_id = ?1 ORDER BY started_at, id"
+    )?;
+    let logs = stmt.query_map([task_id], row_to_time_log)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(logs)
+}
+
+// Registro manual para quando o timer não foi iniciado; sempre é um período fechado
+pub fn add_time_log(conn: &Connection, clock: &dyn Clock, task_id: i64, started_at: &str, ended_at: &str) -> Result<TaskTimeLog, AppError> {
+    ensure_task_exists(conn, task_id)?;
+    let (started_at, ended_at) = validate_time_log(conn, clock, task_id, None, started_at, ended_at)?;
+
+    conn.execute(
+        "INSERT INTO task_time_logs (task_id, started_at, ended_at) VALUES (?1, ?2, ?3)",
+        rusqlite::params![task_id, started_at, ended_at],
+    )?;
+
+    let log_id = conn.last_insert_rowid();
+    log::info!("Registro de tempo {} adicionado à tarefa {}: {} → {}", log_id, task_id, started_at, ended_at);
+
+    Ok(TaskTimeLog { id: Some(log_id), task_id, started_at, ended_at: Some(ended_at) })
+}
+
+// O log em andamento pertence ao timer: só pode ser corrigido depois de pausar a tarefa
+pub fn update_time_log(conn: &Connection, clock: &dyn Clock, log_id: i64, started_at: &str, ended_at: &str) -> Result<TaskTimeLog, AppError> {
+    let current = get_time_log(conn, log_id)?;
+    if current.ended_at.is_none() {
+        return Err(AppError::TimeLogInProgress { log_id });
+    }
+
+    let (started_at, ended_at) = validate_time_log(conn, clock, current.task_id, Some(log_id), started_at, ended_at)?;
+
+    conn.execute(
+        "UPDATE task_time_logs SET started_at = ?1, ended_at = ?2 WHERE id = ?3",
+        rusqlite::params![started_at, ended_at, log_id],
+    )?;
+
+    log::info!("Registro de tempo {} da tarefa {} ajustado: {} → {}", log_id, current.task_id, started_at, ended_at);
+
+    Ok(TaskTimeLog { id: Some(log_id), task_id: current.task_id, started_at, ended_at: Some(ended_at) })
+}
+
+// Devolve a tarefa do registro removido, para recalcular o tempo restante
+pub fn delete_time_log(conn: &Connection, log_id: i64) -> Result<i64, AppError> {
+    let current = get_time_log(conn, log_id)?;
+    if current.ended_at.is_none() {
+        return Err(AppError::TimeLogInProgress { log_id });
+    }
+
+    conn.execute("DELETE FROM task_time_logs WHERE id = ?1", [log_id])?;
+    log::info!("Registro de tempo {} da tarefa {} removido", log_id, current.task_id);
+
+    Ok(current.task_id)
+}
+
+// Início do dia no fuso; onde a meia-noite não existe (horário de verão) o dia começa no primeiro instante válido
+fn day_start(tz: Tz, date: NaiveDate) -> DateTime<Utc> {
+    let mut local = date.and_time(NaiveTime::MIN);
+    loop {
+        match tz.from_local_datetime(&local) {
+            LocalResult::Single(start) | LocalResult::Ambiguous(start, _) => return start.with_timezone(&Utc),
+            LocalResult::None => local += Duration::minutes(15),
+        }
+    }
+}
+
+// Tempo por dia e tarefa entre from e to (inclusive), com os dias contados no fuso informado.
+// Um log que atravessa a meia-noite é dividido entre os dias; logs abertos contam até agora.
+pub fn daily_time_totals(
+    conn: &Connection,
+    clock: &dyn Clock,
+    tz: Tz,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<DailyTimeTotal>, AppError> {
+    if to < from {
+        return Err(AppError::validation("to", "A data final deve ser igual ou posterior à inicial"));
+    }
+
+    let range_start = day_start(tz, from);
+    let range_end = day_start(tz, to + Duration::days(1));
+    let now = clock.now();
+
+    // Filtro largo em texto (os horários gravados nem sempre têm o mesmo sufixo); o corte exato é feito abaixo
+    let mut stmt = conn.prepare(
+        "SELECT task_id, started_at, ended_at FROM task_time_logs
+         WHERE started_at < ?2 AND (ended_at IS NULL OR ended_at >= ?1)
+         ORDER BY started_at"
+    )?;
+    let rows = stmt.query_map(
+        [(range_start - Duration::days(1)).to_rfc3339(), (range_end + Duration::days(1)).to_rfc3339()],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)),
+    )?;
+
+    let mut totals: BTreeMap<(NaiveDate, i64), i64> = BTreeMap::new();
+    for row in rows {
+        let (task_id, started_at, ended_at) = row?;
+        let started_at = parse_log_time("started_at", &started_at)?;
+        let ended_at = match ended_at {
+            Some(ended_at) => parse_log_time("ended_at", &ended_at)?,
+            None => now,
+        };
+
+        let mut cursor = started_at.max(range_start);
+        let end = ended_at.min(range_end);
+        while cursor < end {
+            let date = cursor.with_timezone(&tz).date_naive();
+            let next_day = day_start(tz, date + Duration::days(1)).min(end);
+            *totals.entry((date, task_id)).or_default() += (next_day - cursor).num_seconds();
+            cursor = next_day;
+        }
+    }
+
+    Ok(totals.into_iter()
+        .map(|((date, task_id), seconds)| DailyTimeTotal { date: date.format("%Y-%m-%d").to_string(), task_id, seconds })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+    use crate::database::init_in_memory_database;
+    use crate::tasks::{pause_task, start_task};
+
+    // Tarefa de 1 h; o relógio começa às 12:00 para caber registros na manhã
+    fn setup() -> (Connection, FakeClock, i64) {
+        let conn = init_in_memory_database().unwrap();
+        let clock = FakeClock::new("2026-01-01T12:00:00Z".parse().unwrap());
+        conn.execute(
+            "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at)
+             VALUES ('tarefa', 'teste', 1.0, '2026-01-01', 'pending', '2026-01-01T08:00:00+00:00')",
+            [],
+        ).unwrap();
+        let task_id = conn.last_insert_rowid();
+        (conn, clock, task_id)
+    }
+
+    #[test]
+    fn manual_logs_count_towards_remaining_time() {
+        let (conn, clock, task_id) = setup();
+
+        let log = add_time_log(&conn, &clock, task_id, "2026-01-01T09:00:00Z", "2026-01-01T09:20:00Z").unwrap();
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 40 * 60);
+
+        // Horário com fuso é gravado em UTC, como os logs do timer
+        let log_id = log.id.unwrap();
+        let adjusted = update_time_log(&conn, &clock, log_id, "2026-01-01T06:00:00-03:00", "2026-01-01T06:45:00-03:00").unwrap();
+        assert_eq!(adjusted.started_at, "2026-01-01T09:00:00+00:00");
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 15 * 60);
+
+        assert_eq!(delete_time_log(&conn, log_id), Ok(task_id));
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 60 * 60);
+        assert!(list_task_time_logs(&conn, task_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn manual_logs_reject_invalid_periods_and_overlaps() {
+        let (conn, clock, task_id) = setup();
+        let first = add_time_log(&conn, &clock, task_id, "2026-01-01T09:00:00Z", "2026-01-01T10:00:00Z").unwrap();
+        let first_id = first.id.unwrap();
+
+        assert!(matches!(
+            add_time_log(&conn, &clock, task_id, "2026-01-01T10:00:00Z", "2026-01-01T10:00:00Z"),
+            Err(AppError::Validation { field: "ended_at", .. })
+        ));
+        assert!(matches!(
+            add_time_log(&conn, &clock, task_id, "2026-01-01T11:00:00Z", "2026-01-01T13:00:00Z"),
+            Err(AppError::Validation { field: "ended_at", .. })
+        ));
+        assert!(matches!(
+            add_time_log(&conn, &clock, task_id, "ontem", "2026-01-01T10:00:00Z"),
+            Err(AppError::Validation { field: "started_at", .. })
+        ));
+        assert_eq!(
+            add_time_log(&conn, &clock, task_id, "2026-01-01T09:30:00Z", "2026-01-01T10:30:00Z").unwrap_err(),
+            AppError::TimeLogOverlap { task_id, conflicting_log_id: first_id, conflicting_task_id: task_id }
+        );
+
+        // Encostar no fim do outro registro não é sobreposição; mexer no próprio registro também não
+        let second = add_time_log(&conn, &clock, task_id, "2026-01-01T10:00:00Z", "2026-01-01T10:30:00Z").unwrap();
+        update_time_log(&conn, &clock, first_id, "2026-01-01T08:30:00Z", "2026-01-01T09:45:00Z").unwrap();
+        assert_eq!(
+            update_time_log(&conn, &clock, second.id.unwrap(), "2026-01-01T09:40:00Z", "2026-01-01T10:30:00Z").unwrap_err(),
+            AppError::TimeLogOverlap { task_id, conflicting_log_id: first_id, conflicting_task_id: task_id }
+        );
+
+        assert_eq!(delete_time_log(&conn, 999), Err(AppError::TimeLogNotFound { log_id: 999 }));
+        assert_eq!(list_task_time_logs(&conn, 999).unwrap_err(), AppError::TaskNotFound { task_id: 999 });
+    }
+
+    #[test]
+    fn manual_logs_cannot_overlap_logs_of_other_tasks() {
+        let (conn, clock, task_a) = setup();
+        conn.execute(
+            "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at)
+             VALUES ('outra', 'teste', 1.0, '2026-01-01', 'pending', '2026-01-01T08:00:00+00:00')",
+            [],
+        ).unwrap();
+        let task_b = conn.last_insert_rowid();
+
+        let log_a = add_time_log(&conn, &clock, task_a, "2026-01-01T10:00:00Z", "2026-01-01T11:00:00Z").unwrap();
+        assert_eq!(
+            add_time_log(&conn, &clock, task_b, "2026-01-01T10:30:00Z", "2026-01-01T11:30:00Z").unwrap_err(),
+            AppError::TimeLogOverlap { task_id: task_b, conflicting_log_id: log_a.id.unwrap(), conflicting_task_id: task_a }
+        );
+
+        // Em sequência não há conflito
+        add_time_log(&conn, &clock, task_b, "2026-01-01T11:00:00Z", "2026-01-01T11:30:00Z").unwrap();
+    }
+
+    #[test]
+    fn open_log_blocks_overlap_and_edits_until_paused() {
+        let (conn, clock, task_id) = setup();
+        start_task(&conn, &clock, task_id, None, None).unwrap();
+        clock.advance(chrono::Duration::minutes(10));
+
+        let open = list_task_time_logs(&conn, task_id).unwrap().remove(0);
+        let open_id = open.id.unwrap();
+        assert_eq!(open.ended_at, None);
+
+        // O log aberto vai até agora (12:10)
+        assert_eq!(
+            add_time_log(&conn, &clock, task_id, "2026-01-01T12:05:00Z", "2026-01-01T12:08:00Z").unwrap_err(),
+            AppError::TimeLogOverlap { task_id, conflicting_log_id: open_id, conflicting_task_id: task_id }
+        );
+        assert_eq!(delete_time_log(&conn, open_id), Err(AppError::TimeLogInProgress { log_id: open_id }));
+        assert_eq!(
+            update_time_log(&conn, &clock, open_id, "2026-01-01T12:00:00Z", "2026-01-01T12:05:00Z").unwrap_err(),
+            AppError::TimeLogInProgress { log_id: open_id }
+        );
+
+        pause_task(&conn, &clock, task_id).unwrap();
+        update_time_log(&conn, &clock, open_id, "2026-01-01T12:00:00Z", "2026-01-01T12:05:00Z").unwrap();
+        assert_eq!(get_task_remaining_time(&conn, &clock, task_id).unwrap(), 55 * 60);
+    }
+
+    #[test]
+    fn daily_totals_split_logs_at_midnight_in_the_user_timezone() {
+        let (conn, clock, task_id) = setup();
+        clock.set("2026-04-01T00:00:00Z".parse().unwrap());
+        let day = |value: &str| value.parse::<NaiveDate>().unwrap();
+        let totals = |tz: Tz, from: &str, to: &str| {
+            daily_time_totals(&conn, &clock, tz, day(from), day(to)).unwrap()
+                .into_iter().map(|total| (total.date, total.seconds)).collect::<Vec<_>>()
+        };
+
+        // 21:00 → 03:00 UTC: em São Paulo (UTC-3) é 18:00 → 00:00, tudo no dia 1
+        add_time_log(&conn, &clock, task_id, "2026-01-01T21:00:00Z", "2026-01-02T03:00:00Z").unwrap();
+        assert_eq!(totals(Tz::UTC, "2026-01-01", "2026-01-02"),
+            vec![("2026-01-01".to_string(), 3 * 3600), ("2026-01-02".to_string(), 3 * 3600)]);
+        assert_eq!(totals(chrono_tz::America::Sao_Paulo, "2026-01-01", "2026-01-02"),
+            vec![("2026-01-01".to_string(), 6 * 3600)]);
+        // O intervalo corta o log: só o dia pedido entra
+        assert_eq!(totals(Tz::UTC, "2026-01-02", "2026-01-02"), vec![("2026-01-02".to_string(), 3 * 3600)]);
+
+        // Dia da mudança para o horário de verão em Berlim tem 23 h
+        add_time_log(&conn, &clock, task_id, "2026-03-28T20:00:00Z", "2026-03-30T00:00:00Z").unwrap();
+        assert_eq!(totals(chrono_tz::Europe::Berlin, "2026-03-28", "2026-03-30"), vec![
+            ("2026-03-28".to_string(), 3 * 3600),
+            ("2026-03-29".to_string(), 23 * 3600),
+            ("2026-03-30".to_string(), 2 * 3600),
+        ]);
+
+        assert!(matches!(
+            daily_time_totals(&conn, &clock, Tz::UTC, day("2026-01-02"), day("2026-01-01")),
+            Err(AppError::Validation { field: "to", .. })
+        ));
+    }
+
+    #[test]
+    fn today_follows_the_configured_timezone() {
+        let (conn, clock, task_id) = setup();
+        // 23:30 UTC de 1º de janeiro já é dia 2 em Tóquio
+        clock.set("2026-01-01T23:30:00Z".parse().unwrap());
+
+        let today_ids = |tz: Tz| crate::tasks::get_today_tasks(&conn, &clock, tz).unwrap()
+            .into_iter().filter_map(|task| task.id).collect::<Vec<_>>();
+        assert_eq!(today_ids(Tz::UTC), vec![task_id]);
+
+        crate::settings::save_timezone(&conn, "Asia/Tokyo").unwrap();
+        let tz = crate::settings::resolve_timezone(&conn, None).unwrap();
+        assert_eq!(tz, chrono_tz::Asia::Tokyo);
+        assert_eq!(today_ids(tz), Vec::<i64>::new());
+        assert_eq!(crate::settings::resolve_timezone(&conn, Some("UTC")).unwrap(), Tz::UTC);
+        assert!(crate::settings::save_timezone(&conn, "Marte/Olympus").is_err());
+    }
+}
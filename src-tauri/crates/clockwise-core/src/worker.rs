@@ -0,0 +1,174 @@
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use rusqlite::Connection;
+
+use crate::error::AppError;
+
+type Job = Box<dyn FnOnce(&mut Connection) + Send>;
+
+// Única dona da conexão: os pedidos entram numa fila e rodam em ordem numa thread própria,
+// fora do executor assíncrono
+#[derive(Clone)]
+pub struct DbWorker {
+    sender: mpsc::Sender<Job>,
+}
+
+impl DbWorker {
+    pub fn spawn(mut conn: Connection) -> Result<DbWorker, AppError> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+
+        thread::Builder::new()
+            .name("clockwise-db".to_string())
+            .spawn(move || {
+                // Termina quando a última cópia do DbWorker é descartada
+                for job in receiver {
+                    // Um pedido com pânico não derruba a fila; quem esperava recebe erro
+                    if panic::catch_unwind(AssertUnwindSafe(|| job(&mut conn))).is_err() {
+                        log::error!(target: "database", "Pedido ao banco terminou com pânico");
+                    }
+                }
+                log::debug!(target: "database", "Thread do banco encerrada");
+            })?;
+
+        Ok(DbWorker { sender })
+    }
+
+    // Enfileira o trabalho; a resposta pode ser aguardada com .await ou, fora do executor, com wait()
+    pub fn call<T, F>(&self, f: F) -> Reply<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, AppError> + Send + 'static,
+    {
+        let shared = Arc::new(Shared { state: Mutex::new(ReplyState::Waiting(None)), ready: Condvar::new() });
+        let completer = Completer { shared: Some(shared.clone()) };
+
+        let job: Job = Box::new(move |conn| completer.complete(f(conn)));
+        // Com a thread encerrada o pedido volta no erro e é descartado, o que já resolve a resposta com erro
+        let _ = self.sender.send(job);
+
+        Reply { shared }
+    }
+}
+
+enum ReplyState<T> {
+    Waiting(Option<Waker>),
+    Done(Result<T, AppError>),
+    Taken,
+}
+
+struct Shared<T> {
+    state: Mutex<ReplyState<T>>,
+    ready: Condvar,
+}
+
+// Garante que a resposta sempre chega, mesmo se o pedido for descartado sem rodar
+struct Completer<T> {
+    shared: Option<Arc<Shared<T>>>,
+}
+
+impl<T> Completer<T> {
+    fn complete(mut self, result: Result<T, AppError>) {
+        if let Some(shared) = self.shared.take() {
+            resolve(&shared, result);
+        }
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.take() {
+            resolve(&shared, Err(AppError::Database("O pedido ao banco foi interrompido".to_string())));
+        }
+    }
+}
+
+fn resolve<T>(shared: &Shared<T>, result: Result<T, AppError>) {
+    let mut state = shared.state.lock().unwrap_or_else(|e| e.into_inner());
+    let previous = std::mem::replace(&mut *state, ReplyState::Done(result));
+    drop(state);
+
+    if let ReplyState::Waiting(Some(waker)) = previous {
+        waker.wake();
+    }
+    shared.ready.notify_all();
+}
+
+pub struct Reply<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Reply<T> {
+    // Bloqueia a thread atual; para threads próprias (ex.: motor de timer), nunca dentro do executor
+    pub fn wait(self) -> Result<T, AppError> {
+        let mut state = self.shared.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            match std::mem::replace(&mut *state, ReplyState::Taken) {
+                ReplyState::Done(result) => return result,
+                waiting => *state = waiting,
+            }
+            state = self.shared.ready.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+impl<T> Future for Reply<T> {
+    type Output = Result<T, AppError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.state.lock().unwrap_or_else(|e| e.into_inner());
+        match std::mem::replace(&mut *state, ReplyState::Taken) {
+            ReplyState::Done(result) => Poll::Ready(result),
+            ReplyState::Taken => panic!("Reply consultada depois de concluída"),
+            ReplyState::Waiting(_) => {
+                *state = ReplyState::Waiting(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_worker() -> DbWorker {
+        DbWorker::spawn(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn requests_run_in_order_on_the_worker_thread() {
+        let worker = spawn_worker();
+        worker.call(|conn| Ok(conn.execute_batch("CREATE TABLE fila (n INTEGER)")?)).wait().unwrap();
+
+        let pending: Vec<_> = (0..5)
+            .map(|n| worker.call(move |conn| Ok(conn.execute("INSERT INTO fila (n) VALUES (?1)", [n])?)))
+            .collect();
+        for reply in pending {
+            reply.wait().unwrap();
+        }
+
+        let order = worker.call(|conn| {
+            let mut stmt = conn.prepare("SELECT n FROM fila ORDER BY rowid")?;
+            let values = stmt.query_map([], |row| row.get::<_, i64>(0))?.collect::<Result<Vec<_>, _>>()?;
+            Ok((values, thread::current().name().map(str::to_string)))
+        }).wait().unwrap();
+
+        assert_eq!(order, (vec![0, 1, 2, 3, 4], Some("clockwise-db".to_string())));
+    }
+
+    #[test]
+    fn panicking_request_does_not_stop_the_worker() {
+        let worker = spawn_worker();
+
+        let failed = worker.call(|_| -> Result<(), AppError> { panic!("falha simulada") }).wait();
+        assert!(matches!(failed, Err(AppError::Database(_))));
+
+        assert_eq!(worker.call(|conn| Ok(conn.query_row("SELECT 1 + 1", [], |row| row.get::<_, i64>(0))?)).wait(), Ok(2));
+    }
+}
@@ -0,0 +1,326 @@
+// Cenários do ciclo de vida de tarefas e sessões Pomodoro, com o esquema real e relógio falso
+use chrono::Duration;
+use clockwise_core::database::init_in_memory_database;
+use clockwise_core::pomodoro::{
+    advance_pomodoro_sessions, check_and_advance_pomodoro_sessions, extend_active_pomodoro_session, get_active_pomodoro_session,
+    get_active_timers,
+};
+use clockwise_core::profiles::create_pomodoro_profile;
+use clockwise_core::tasks::{add_task, complete_task, get_task_by_id, pause_task, resume_task, start_task, update_task};
+use clockwise_core::time_logs::get_task_remaining_time;
+use clockwise_core::{AppError, Clock, FakeClock, OfflinePolicy, PomodoroProfile, SessionType, TaskStatus, TaskUpdate};
+use rusqlite::Connection;
+
+struct Harness {
+    conn: Connection,
+    clock: FakeClock,
+    profile_id: i64,
+}
+
+impl Harness {
+    // Perfil curto (10/2/5 min, pausa longa a cada 2 rodadas) deixa o ciclo inteiro em poucos passos
+    fn new() -> Harness {
+        let conn = init_in_memory_database().unwrap();
+        let clock = FakeClock::new("2026-03-02T09:00:00Z".parse().unwrap());
+        let profile = create_pomodoro_profile(&conn, &clock, PomodoroProfile {
+            id: None,
+            name: "Curto".to_string(),
+            work_seconds: 600,
+            short_break_seconds: 120,
+            long_break_seconds: 300,
+            rounds_before_long_break: 2,
+            total_rounds: 3,
+            is_default: false,
+        }).unwrap();
+
+        Harness { conn, clock, profile_id: profile.id.unwrap() }
+    }
+
+    // 0,5 h com o perfil curto: trabalho, pausa curta, trabalho, pausa longa, trabalho
+    fn add_task(&self, name: &str) -> i64 {
+        let task = add_task(
+            &self.conn,
+            &self.clock,
+            name.to_string(),
+            "teste".to_string(),
+            0.5,
+            "2026-03-02".to_string(),
+            Some(self.profile_id),
+        ).unwrap();
+        task.id.unwrap()
+    }
+
+    fn advance(&self, seconds: i64) -> Vec<i64> {
+        self.clock.advance(Duration::seconds(seconds));
+        check_and_advance_pomodoro_sessions(&self.conn, &self.clock).unwrap()
+    }
+
+    fn status(&self, task_id: i64) -> TaskStatus {
+        get_task_by_id(&self.conn, task_id).unwrap().unwrap().status
+    }
+
+    fn active_session(&self, task_id: i64) -> Option<(i32, SessionType)> {
+        get_active_pomodoro_session(&self.conn, task_id).unwrap()
+            .map(|(session, _)| (session.session_number, session.session_type))
+    }
+
+    fn remaining(&self, task_id: i64) -> i64 {
+        get_task_remaining_time(&self.conn, &self.clock, task_id).unwrap()
+    }
+
+    fn open_logs(&self, task_id: i64) -> i64 {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM task_time_logs WHERE task_id = ?1 AND ended_at IS NULL",
+            [task_id],
+            |row| row.get(0),
+        ).unwrap()
+    }
+}
+
+#[test]
+fn full_cycle_runs_work_break_long_break_until_completed() {
+    let h = Harness::new();
+    let task_id = h.add_task("ciclo");
+
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+    assert_eq!(h.status(task_id), TaskStatus::InProgress);
+    assert_eq!(h.active_session(task_id), Some((1, SessionType::Work)));
+
+    assert_eq!(h.advance(600), vec![task_id]);
+    assert_eq!(h.status(task_id), TaskStatus::Waiting);
+    assert_eq!(h.active_session(task_id), Some((2, SessionType::Break)));
+    assert_eq!(h.open_logs(task_id), 0);
+
+    assert_eq!(h.advance(120), vec![task_id]);
+    assert_eq!(h.status(task_id), TaskStatus::InProgress);
+    assert_eq!(h.active_session(task_id), Some((3, SessionType::Work)));
+    assert_eq!(h.open_logs(task_id), 1);
+
+    h.advance(600);
+    assert_eq!(h.status(task_id), TaskStatus::Waiting);
+    assert_eq!(h.active_session(task_id), Some((4, SessionType::Break)));
+
+    // Pausa longa: um segundo antes do fim nada muda
+    assert!(h.advance(299).is_empty());
+    h.advance(1);
+    assert_eq!(h.active_session(task_id), Some((5, SessionType::Work)));
+
+    h.advance(600);
+    assert_eq!(h.status(task_id), TaskStatus::Completed);
+    assert_eq!(h.active_session(task_id), None);
+    assert_eq!(h.remaining(task_id), 0);
+}
+
+#[test]
+fn plan_ending_on_a_long_break_round_keeps_the_long_break() {
+    let h = Harness::new();
+    // 20 min = 2 rodadas: a segunda fecha o bloco de 2, então o ciclo termina com a pausa longa
+    let task_id = add_task(&h.conn, &h.clock, "curta".to_string(), "teste".to_string(), 1.0 / 3.0,
+        "2026-03-02".to_string(), Some(h.profile_id)).unwrap().id.unwrap();
+
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+    h.advance(600);
+    assert_eq!(h.active_session(task_id), Some((2, SessionType::Break)));
+    h.advance(120);
+    h.advance(600);
+    assert_eq!(h.status(task_id), TaskStatus::Waiting);
+    assert_eq!(h.active_session(task_id), Some((4, SessionType::Break)));
+
+    h.advance(300);
+    assert_eq!(h.status(task_id), TaskStatus::Completed);
+    assert_eq!(h.active_session(task_id), None);
+    assert_eq!(h.remaining(task_id), 0);
+}
+
+#[test]
+fn extension_is_not_counted_as_planned_work_when_the_estimate_changes() {
+    let h = Harness::new();
+    let task_id = h.add_task("estendida");
+
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+    h.advance(300);
+    extend_active_pomodoro_session(&h.conn, &h.clock, task_id, 300).unwrap();
+
+    // 20 min estimados: a primeira sessão cobre 10 (mais 5 de extensão), falta uma de 10
+    update_task(&h.conn, &h.clock, task_id, &TaskUpdate {
+        estimated_hours: Some(1.0 / 3.0),
+        ..TaskUpdate::default()
+    }).unwrap();
+
+    let mut stmt = h.conn.prepare(
+        "SELECT session_type, duration_seconds FROM pomodoro_sessions WHERE task_id = ?1 ORDER BY session_number"
+    ).unwrap();
+    let sessions: Vec<(SessionType, i32)> = stmt.query_map([task_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(sessions, vec![
+        (SessionType::Work, 900),
+        (SessionType::Break, 120),
+        (SessionType::Work, 600),
+        (SessionType::Break, 300),
+    ]);
+
+    // A sessão estendida termina 15 min depois do início
+    assert!(h.advance(599).is_empty());
+    assert_eq!(h.advance(1), vec![task_id]);
+    assert_eq!(h.active_session(task_id), Some((2, SessionType::Break)));
+}
+
+#[test]
+fn starting_a_task_during_its_break_is_rejected() {
+    let h = Harness::new();
+    let task_id = h.add_task("pausa curta");
+
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+    h.advance(600);
+    assert_eq!(h.status(task_id), TaskStatus::Waiting);
+    assert_eq!(h.open_logs(task_id), 0);
+
+    assert_eq!(
+        start_task(&h.conn, &h.clock, task_id, None, None),
+        Err(AppError::TaskAlreadyActive { task_id })
+    );
+    assert_eq!(h.status(task_id), TaskStatus::Waiting);
+    assert_eq!(h.active_session(task_id), Some((2, SessionType::Break)));
+    let running: i64 = h.conn.query_row(
+        "SELECT COUNT(*) FROM pomodoro_sessions WHERE task_id = ?1 AND status = 'running'",
+        [task_id],
+        |row| row.get(0),
+    ).unwrap();
+    assert_eq!(running, 1);
+}
+
+#[test]
+fn pause_and_resume_continue_the_interrupted_session() {
+    let h = Harness::new();
+    let task_id = h.add_task("pausa");
+
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+    h.advance(240);
+    pause_task(&h.conn, &h.clock, task_id).unwrap();
+    assert_eq!(h.status(task_id), TaskStatus::Paused);
+    assert_eq!(h.active_session(task_id), None);
+
+    // Tempo parado não conta para a tarefa nem encerra a sessão
+    assert!(h.advance(3600).is_empty());
+    assert_eq!(h.remaining(task_id), 1800 - 240);
+
+    resume_task(&h.conn, &h.clock, task_id).unwrap();
+    assert_eq!(h.status(task_id), TaskStatus::InProgress);
+    assert_eq!(h.active_session(task_id), Some((1, SessionType::Work)));
+
+    // A sessão retomada termina só com o que faltava (600 - 240)
+    assert!(h.advance(359).is_empty());
+    assert_eq!(h.advance(1), vec![task_id]);
+    assert_eq!(h.active_session(task_id), Some((2, SessionType::Break)));
+    assert_eq!(h.remaining(task_id), 1800 - 600);
+}
+
+#[test]
+fn timer_tick_carries_session_and_task_remaining_time() {
+    let h = Harness::new();
+    let task_id = h.add_task("tick");
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+    h.advance(240);
+
+    let timers = get_active_timers(&h.conn, &h.clock).unwrap();
+    assert_eq!(timers.len(), 1);
+    assert_eq!((timers[0].task_id, timers[0].session_number), (task_id, 1));
+    assert_eq!(timers[0].remaining_seconds, 600 - 240);
+    assert_eq!(timers[0].task_remaining_seconds, 1800 - 240);
+
+    // Na pausa o relógio da tarefa para, o da sessão continua
+    h.advance(360);
+    h.advance(60);
+    let timers = get_active_timers(&h.conn, &h.clock).unwrap();
+    assert_eq!((timers[0].session_type, timers[0].remaining_seconds), (SessionType::Break, 60));
+    assert_eq!(timers[0].task_remaining_seconds, 1800 - 600);
+}
+
+#[test]
+fn resume_requires_a_paused_task() {
+    let h = Harness::new();
+    let task_id = h.add_task("pendente");
+
+    assert_eq!(
+        resume_task(&h.conn, &h.clock, task_id),
+        Err(AppError::TaskNotPaused { task_id, status: TaskStatus::Pending })
+    );
+}
+
+#[test]
+fn stop_and_start_pauses_the_running_task() {
+    let h = Harness::new();
+    let first = h.add_task("primeira");
+    let second = h.add_task("segunda");
+
+    start_task(&h.conn, &h.clock, first, None, None).unwrap();
+    h.advance(300);
+
+    assert_eq!(
+        start_task(&h.conn, &h.clock, second, None, None),
+        Err(AppError::AnotherTaskActive { task_id: second, active_task_id: first })
+    );
+
+    start_task(&h.conn, &h.clock, second, Some(true), None).unwrap();
+    assert_eq!(h.status(first), TaskStatus::Paused);
+    assert_eq!(h.active_session(first), None);
+    assert_eq!(h.open_logs(first), 0);
+    assert_eq!(h.remaining(first), 1800 - 300);
+
+    assert_eq!(h.status(second), TaskStatus::InProgress);
+    assert_eq!(h.active_session(second), Some((1, SessionType::Work)));
+}
+
+#[test]
+fn crash_recovery_replays_missed_transitions() {
+    let h = Harness::new();
+    let task_id = h.add_task("replay");
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+
+    // App fechado por 15 min: trabalho (10) e pausa curta (2) terminaram sem ninguém ver
+    h.clock.advance(Duration::minutes(15));
+    let recovered = advance_pomodoro_sessions(&h.conn, h.clock.now(), OfflinePolicy::Replay).unwrap();
+
+    assert_eq!(recovered, vec![task_id]);
+    assert_eq!(h.active_session(task_id), Some((3, SessionType::Work)));
+    assert_eq!(h.status(task_id), TaskStatus::InProgress);
+    // Trabalho conta só nas sessões de trabalho: 10 min + 3 min da segunda sessão
+    assert_eq!(h.remaining(task_id), 1800 - 600 - 180);
+}
+
+#[test]
+fn crash_recovery_can_pause_at_first_missed_boundary() {
+    let h = Harness::new();
+    let task_id = h.add_task("fronteira");
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+
+    h.clock.advance(Duration::hours(2));
+    advance_pomodoro_sessions(&h.conn, h.clock.now(), OfflinePolicy::PauseAtBoundary).unwrap();
+
+    assert_eq!(h.status(task_id), TaskStatus::Paused);
+    assert_eq!(h.active_session(task_id), None);
+    assert_eq!(h.remaining(task_id), 1800 - 600);
+
+    // Ao retomar, segue para a pausa curta que estava por vir
+    resume_task(&h.conn, &h.clock, task_id).unwrap();
+    assert_eq!(h.active_session(task_id), Some((2, SessionType::Break)));
+    assert_eq!(h.status(task_id), TaskStatus::Waiting);
+}
+
+#[test]
+fn completing_a_running_task_closes_its_log() {
+    let h = Harness::new();
+    let task_id = h.add_task("concluir");
+    start_task(&h.conn, &h.clock, task_id, None, None).unwrap();
+    h.advance(420);
+
+    complete_task(&h.conn, &h.clock, task_id).unwrap();
+    assert_eq!(h.status(task_id), TaskStatus::Completed);
+    assert_eq!(h.open_logs(task_id), 0);
+
+    // Depois de concluída, o relógio andando não muda o tempo trabalhado
+    h.advance(600);
+    assert_eq!(h.remaining(task_id), 1800 - 420);
+}
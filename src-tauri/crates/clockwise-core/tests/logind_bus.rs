@@ -0,0 +1,145 @@
+// Sinais do logind simulados num barramento privado: o teste sobe o próprio dbus-daemon
+// e assume o nome org.freedesktop.login1 nele, sem depender do barramento do sistema.
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::os::unix::net::UnixStream;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use clockwise_core::system_events::{Bus, LogindMonitor, SystemEventKind, SystemEventSource};
+use clockwise_core::{Clock, FakeClock};
+use zbus::zvariant::{OwnedFd, OwnedObjectPath};
+
+const OWN_SESSION: &str = "/org/freedesktop/login1/session/_331";
+const OTHER_SESSION: &str = "/org/freedesktop/login1/session/_332";
+const MANAGER: &str = "/org/freedesktop/login1";
+
+struct PrivateBus {
+    daemon: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    // None quando não há dbus-daemon instalado: o teste é pulado em vez de falhar
+    fn start() -> Option<PrivateBus> {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .ok()?;
+
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap()).read_line(&mut address).unwrap();
+        assert!(!address.trim().is_empty(), "dbus-daemon não informou o endereço");
+
+        Some(PrivateBus { daemon, address: address.trim().to_string() })
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+// Manager do logind reduzido ao que o monitor usa. Cada inibidor entregue é uma ponta de um par
+// de sockets: a outra fica aqui e lê EOF quando o monitor fecha o descritor
+#[derive(Clone, Default)]
+struct StandInManager {
+    inhibitors: Arc<Mutex<Vec<UnixStream>>>,
+}
+
+#[zbus::interface(name = "org.freedesktop.login1.Manager")]
+impl StandInManager {
+    fn get_session(&self, _id: &str) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(OWN_SESSION).unwrap()
+    }
+
+    #[zbus(name = "GetSessionByPID")]
+    fn get_session_by_pid(&self, _pid: u32) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(OWN_SESSION).unwrap()
+    }
+
+    fn inhibit(&self, what: &str, _who: &str, _why: &str, mode: &str) -> zbus::fdo::Result<OwnedFd> {
+        assert_eq!((what, mode), ("sleep:shutdown", "delay"));
+        let (kept, handed_out) = UnixStream::pair().map_err(|e| zbus::fdo::Error::Failed(e.to_string()))?;
+        self.inhibitors.lock().unwrap().push(kept);
+        Ok(std::os::fd::OwnedFd::from(handed_out).into())
+    }
+}
+
+impl StandInManager {
+    fn inhibitor_count(&self) -> usize {
+        self.inhibitors.lock().unwrap().len()
+    }
+
+    // Ok(0) na leitura é o monitor fechando a sua ponta; tempo esgotado é o inibidor ainda seguro
+    fn inhibitor_released(&self, index: usize, wait: Duration) -> bool {
+        let mut inhibitor = self.inhibitors.lock().unwrap()[index].try_clone().unwrap();
+        inhibitor.set_read_timeout(Some(wait)).unwrap();
+        match inhibitor.read(&mut [0u8; 1]) {
+            Ok(0) => true,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => false,
+            other => panic!("leitura inesperada do inibidor: {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn logind_signals_on_a_stand_in_bus_become_events() {
+    let Some(bus) = PrivateBus::start() else {
+        eprintln!("dbus-daemon não encontrado; teste do barramento do logind pulado");
+        return;
+    };
+    let clock = Arc::new(FakeClock::new("2026-03-02T22:10:00Z".parse().unwrap()));
+    let manager = StandInManager::default();
+
+    // Quem emite fica com o nome do logind antes de o monitor assinar os sinais
+    let logind = zbus::blocking::connection::Builder::address(bus.address.as_str()).unwrap()
+        .name("org.freedesktop.login1").unwrap()
+        .serve_at(MANAGER, manager.clone()).unwrap()
+        .build()
+        .unwrap();
+
+    let mut monitor = LogindMonitor::connect(Bus::Address(bus.address.clone()), clock.clone()).unwrap();
+    assert_eq!(manager.inhibitor_count(), 1);
+    assert!(!manager.inhibitor_released(0, Duration::from_millis(200)));
+
+    let emit = |path: &str, interface: &str, member: &str, starting: Option<bool>| match starting {
+        Some(starting) => logind.emit_signal(None::<&str>, path, interface, member, &starting).unwrap(),
+        None => logind.emit_signal(None::<&str>, path, interface, member, &()).unwrap(),
+    };
+    let mut next = || monitor.next_event().unwrap().expect("monitor encerrado antes do sinal");
+
+    // O Unlock de outra sessão não pode liberar o bloqueio desta
+    emit(OWN_SESSION, "org.freedesktop.login1.Session", "Lock", None);
+    emit(OTHER_SESSION, "org.freedesktop.login1.Session", "Unlock", None);
+    emit(MANAGER, "org.freedesktop.login1.Manager", "PrepareForSleep", Some(true));
+
+    let lock = next();
+    let sleep = next();
+    assert_eq!((lock.kind, sleep.kind), (SystemEventKind::Lock, SystemEventKind::Sleep));
+
+    // Enquanto o log não foi gravado o sistema espera; depois o inibidor é solto
+    assert!(!manager.inhibitor_released(0, Duration::from_millis(200)));
+    monitor.event_handled(&sleep);
+    assert!(manager.inhibitor_released(0, Duration::from_secs(5)));
+
+    clock.advance(chrono::Duration::hours(9));
+    emit(MANAGER, "org.freedesktop.login1.Manager", "PrepareForSleep", Some(false));
+    emit(OWN_SESSION, "org.freedesktop.login1.Session", "Unlock", None);
+
+    let mut next = || monitor.next_event().unwrap().expect("monitor encerrado antes do sinal");
+    let wake = next();
+    let unlock = next();
+    assert_eq!((wake.kind, unlock.kind), (SystemEventKind::Wake, SystemEventKind::Unlock));
+
+    // Ao acordar, um inibidor novo para a próxima suspensão
+    assert_eq!(manager.inhibitor_count(), 2);
+    assert!(!manager.inhibitor_released(1, Duration::from_millis(200)));
+
+    // O horário do evento vem do relógio injetado, no momento em que o sinal chega
+    assert_eq!(sleep.at, "2026-03-02T22:10:00Z".parse::<chrono::DateTime<chrono::Utc>>().unwrap());
+    assert_eq!(unlock.at, clock.now());
+}
@@ -0,0 +1,425 @@
+use std::sync::{Arc, Mutex};
+
+use clockwise_core::integrity::{self, IntegrityReport};
+use clockwise_core::{idle, pomodoro, profiles, settings, tasks, time_logs};
+use clockwise_core::{
+    AppError, Clock, DailyTimeTotal, DbWorker, OfflinePolicy, PomodoroProfile, RecoverySummary, Task, TaskChangedPayload,
+    TaskTimeLog, TaskUpdate, TaskWithActiveSession,
+};
+use tauri::{Emitter, State};
+
+// Comandos não tocam na conexão: enviam pedidos ao worker e aguardam a resposta sem bloquear o executor
+pub struct DatabaseState {
+    pub worker: DbWorker,
+    pub clock: Arc<dyn Clock>,
+}
+
+// Resultado da recuperação após queda, guardado até o frontend pedir: eventos emitidos no setup
+// se perdem porque a janela ainda não está ouvindo
+pub struct RecoveryState(pub Mutex<Option<RecoverySummary>>);
+
+#[tauri::command]
+pub async fn load_tasks(db_state: State<'_, DatabaseState>) -> Result<Vec<Task>, AppError> {
+    db_state.worker.call(|conn| tasks::load_tasks(conn)).await
+}
+
+#[tauri::command]
+pub async fn add_task(
+    name: String,
+    user: String,
+    estimated_hours: f64,
+    scheduled_date: String,
+    profile_id: Option<i64>,
+    db_state: State<'_, DatabaseState>
+) -> Result<Task, AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        let task = tasks::add_task(&tx, &*clock, name, user, estimated_hours, scheduled_date, profile_id)?;
+        tx.commit()?;
+        Ok(task)
+    }).await
+}
+
+#[tauri::command]
+pub async fn update_task(
+    task_id: i64,
+    name: Option<String>,
+    user: Option<String>,
+    estimated_hours: Option<f64>,
+    scheduled_date: Option<String>,
+    app: tauri::AppHandle,
+    db_state: State<'_, DatabaseState>
+) -> Result<Task, AppError> {
+    let update = TaskUpdate {
+        name,
+        user,
+        estimated_hours,
+        scheduled_date,
+    };
+
+    let clock = db_state.clock.clone();
+    let (task, remaining_seconds) = db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        let task = tasks::update_task(&tx, &*clock, task_id, &update)?;
+
+        // Tempo restante recalculado com a estimativa (possivelmente) nova
+        let remaining_seconds = time_logs::calculate_task_remaining_time(&tx, &*clock, task_id, task.estimated_hours)?;
+
+        tx.commit()?;
+        Ok((task, remaining_seconds))
+    }).await?;
+
+    log::info!("Tarefa {} atualizada - restante: {}s", task_id, remaining_seconds);
+
+    let _ = app.emit("tasks-changed", TaskChangedPayload { task_id, remaining_seconds });
+
+    Ok(task)
+}
+
+#[tauri::command]
+pub async fn list_pomodoro_profiles(db_state: State<'_, DatabaseState>) -> Result<Vec<PomodoroProfile>, AppError> {
+    db_state.worker.call(|conn| profiles::list_pomodoro_profiles(conn)).await
+}
+
+#[tauri::command]
+pub async fn create_pomodoro_profile(
+    name: String,
+    work_seconds: i32,
+    short_break_seconds: i32,
+    long_break_seconds: i32,
+    rounds_before_long_break: i32,
+    total_rounds: i32,
+    db_state: State<'_, DatabaseState>
+) -> Result<PomodoroProfile, AppError> {
+    let profile = PomodoroProfile {
+        id: None,
+        name,
+        work_seconds,
+        short_break_seconds,
+        long_break_seconds,
+        rounds_before_long_break,
+        total_rounds,
+        is_default: false,
+    };
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| profiles::create_pomodoro_profile(conn, &*clock, profile)).await
+}
+
+#[tauri::command]
+pub async fn delete_pomodoro_profile(profile_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        profiles::delete_pomodoro_profile(&tx, profile_id)?;
+        Ok(tx.commit()?)
+    }).await
+}
+
+#[tauri::command]
+pub async fn set_default_pomodoro_profile(profile_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    db_state.worker.call(move |conn| profiles::set_default_pomodoro_profile(conn, profile_id)).await
+}
+
+#[tauri::command]
+pub async fn start_task(
+    task_id: i64,
+    stop_and_start: Option<bool>,
+    profile_id: Option<i64>,
+    db_state: State<'_, DatabaseState>
+) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        // Tudo ou nada: um erro no meio desfaz as mudanças já feitas
+        let tx = conn.transaction()?;
+        tasks::start_task(&tx, &*clock, task_id, stop_and_start, profile_id)?;
+        Ok(tx.commit()?)
+    }).await
+}
+
+#[tauri::command]
+pub async fn check_pomodoro_sessions(db_state: State<'_, DatabaseState>) -> Result<Vec<i64>, AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| pomodoro::check_and_advance_pomodoro_sessions(conn, &*clock)).await
+}
+
+#[tauri::command]
+pub async fn skip_pomodoro_session(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    let next_session = db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        let next_session = pomodoro::skip_active_pomodoro_session(&tx, &*clock, task_id)?;
+        tx.commit()?;
+        Ok(next_session)
+    }).await?;
+
+    log::info!("Sessão pulada na tarefa {} - próxima: {:?}", task_id,
+        next_session.map(|s| s.session_number));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn extend_pomodoro_session(task_id: i64, seconds: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        pomodoro::extend_active_pomodoro_session(&tx, &*clock, task_id, seconds)?;
+        Ok(tx.commit()?)
+    }).await?;
+
+    log::info!("Sessão da tarefa {} estendida em {}s", task_id, seconds);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn restart_pomodoro_session(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        pomodoro::restart_active_pomodoro_session(&tx, &*clock, task_id)?;
+        Ok(tx.commit()?)
+    }).await?;
+
+    log::info!("Sessão da tarefa {} reiniciada", task_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_offline_policy(db_state: State<'_, DatabaseState>) -> Result<OfflinePolicy, AppError> {
+    db_state.worker.call(|conn| Ok(settings::load_offline_policy(conn)?)).await
+}
+
+#[tauri::command]
+pub async fn set_offline_policy(policy: OfflinePolicy, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    db_state.worker.call(move |conn| Ok(settings::set_setting(conn, "offline_policy", policy.as_str())?)).await?;
+    log::info!("Política offline definida para {}", policy.as_str());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_log_level() -> Result<String, AppError> {
+    Ok(log::max_level().as_str().to_lowercase())
+}
+
+#[tauri::command]
+pub async fn set_log_level(level: String, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let level = db_state.worker.call(move |conn| settings::save_log_level(conn, &level)).await?;
+
+    // Vale imediatamente, sem reiniciar o app
+    log::set_max_level(level);
+    log::info!("Nível de log definido para {}", level);
+    Ok(())
+}
+
+// Em segundos; 0 significa detecção de inatividade desligada
+#[tauri::command]
+pub async fn get_idle_threshold(db_state: State<'_, DatabaseState>) -> Result<i64, AppError> {
+    let threshold = db_state.worker.call(|conn| Ok(settings::load_idle_threshold(conn)?)).await?;
+    Ok(threshold.map_or(0, |threshold| threshold.num_seconds()))
+}
+
+#[tauri::command]
+pub async fn set_idle_threshold(seconds: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    db_state.worker.call(move |conn| settings::save_idle_threshold(conn, seconds)).await?;
+    log::info!(target: "idle", "Limite de inatividade definido para {}s", seconds);
+    Ok(())
+}
+
+// Resposta à pergunta feita quando o usuário volta (evento idle-returned).
+// O período vem do que o monitor guardou no banco, nunca do frontend
+#[tauri::command]
+pub async fn resolve_idle_time(
+    keep: bool,
+    app: tauri::AppHandle,
+    db_state: State<'_, DatabaseState>
+) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    let (task_id, remaining_seconds) = db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        let period = idle::resolve_idle_period(&tx, &*clock, keep)?;
+        let remaining_seconds = time_logs::get_task_remaining_time(&tx, &*clock, period.task_id)?;
+        tx.commit()?;
+        Ok((period.task_id, remaining_seconds))
+    }).await?;
+
+    let _ = app.emit("tasks-changed", TaskChangedPayload { task_id, remaining_seconds });
+    Ok(())
+}
+
+// Entregue uma única vez: o aviso de recuperação não deve reaparecer ao recarregar a janela
+#[tauri::command]
+pub async fn take_recovery_summary(recovery: State<'_, RecoveryState>) -> Result<Option<RecoverySummary>, AppError> {
+    Ok(recovery.0.lock()?.take())
+}
+
+#[tauri::command]
+pub async fn check_database(repair: Option<bool>, db_state: State<'_, DatabaseState>) -> Result<IntegrityReport, AppError> {
+    let report = db_state.worker
+        .call(move |conn| integrity::check_database_integrity(conn, repair.unwrap_or(false)))
+        .await?;
+
+    log::info!("Verificação do banco: {} problemas encontrados{}", report.issues.len(),
+        if report.repair_requested { " (reparados)" } else { "" });
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn load_tasks_with_sessions(
+    limit: Option<u32>,
+    offset: Option<u32>,
+    db_state: State<'_, DatabaseState>
+) -> Result<Vec<TaskWithActiveSession>, AppError> {
+    // Sem limite, a lista vem inteira (comportamento anterior do frontend)
+    db_state.worker.call(move |conn| tasks::load_tasks_with_sessions(conn, limit, offset.unwrap_or(0))).await
+}
+
+#[tauri::command]
+pub async fn complete_task(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        // Tudo ou nada: um erro no meio desfaz as mudanças já feitas
+        let tx = conn.transaction()?;
+        tasks::complete_task(&tx, &*clock, task_id)?;
+        Ok(tx.commit()?)
+    }).await
+}
+
+#[tauri::command]
+pub async fn pause_task(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        // Tudo ou nada: um erro no meio desfaz as mudanças já feitas
+        let tx = conn.transaction()?;
+        tasks::pause_task(&tx, &*clock, task_id)?;
+        Ok(tx.commit()?)
+    }).await
+}
+
+#[tauri::command]
+pub async fn resume_task(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        // Tudo ou nada: um erro no meio desfaz as mudanças já feitas
+        let tx = conn.transaction()?;
+        tasks::resume_task(&tx, &*clock, task_id)?;
+        Ok(tx.commit()?)
+    }).await
+}
+
+#[tauri::command]
+pub async fn get_task_remaining_time(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<i64, AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| time_logs::get_task_remaining_time(conn, &*clock, task_id)).await
+}
+
+#[tauri::command]
+pub async fn delete_task(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    db_state.worker.call(move |conn| tasks::delete_task(conn, task_id)).await
+}
+
+// Comandos por data aceitam um fuso IANA explícito. Sem ele (None, ou null do frontend antes de
+// carregar o fuso) vale o gravado nas configurações; nome inválido é erro, nunca troca de fuso
+#[tauri::command]
+pub async fn get_today_tasks(timezone: Option<String>, db_state: State<'_, DatabaseState>) -> Result<Vec<Task>, AppError> {
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        let tz = settings::resolve_timezone(conn, timezone.as_deref())?;
+        tasks::get_today_tasks(conn, &*clock, tz)
+    }).await
+}
+
+// from/to no formato AAAA-MM-DD, ambos inclusivos
+#[tauri::command]
+pub async fn get_daily_time_totals(
+    from: String,
+    to: String,
+    timezone: Option<String>,
+    db_state: State<'_, DatabaseState>
+) -> Result<Vec<DailyTimeTotal>, AppError> {
+    let from = parse_report_date("from", &from)?;
+    let to = parse_report_date("to", &to)?;
+    let clock = db_state.clock.clone();
+    db_state.worker.call(move |conn| {
+        let tz = settings::resolve_timezone(conn, timezone.as_deref())?;
+        time_logs::daily_time_totals(conn, &*clock, tz, from, to)
+    }).await
+}
+
+fn parse_report_date(field: &'static str, value: &str) -> Result<chrono::NaiveDate, AppError> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| AppError::validation(field, format!("Data inválida: {} (esperado AAAA-MM-DD)", value)))
+}
+
+#[tauri::command]
+pub async fn get_timezone(db_state: State<'_, DatabaseState>) -> Result<String, AppError> {
+    let tz = db_state.worker.call(|conn| Ok(settings::load_timezone(conn)?)).await?;
+    Ok(tz.name().to_string())
+}
+
+#[tauri::command]
+pub async fn set_timezone(timezone: String, db_state: State<'_, DatabaseState>) -> Result<String, AppError> {
+    let tz = db_state.worker.call(move |conn| settings::save_timezone(conn, &timezone)).await?;
+    log::info!(target: "database", "Fuso horário definido para {}", tz.name());
+    Ok(tz.name().to_string())
+}
+
+#[tauri::command]
+pub async fn list_time_logs(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<Vec<TaskTimeLog>, AppError> {
+    db_state.worker.call(move |conn| time_logs::list_task_time_logs(conn, task_id)).await
+}
+
+#[tauri::command]
+pub async fn add_time_log(
+    task_id: i64,
+    started_at: String,
+    ended_at: String,
+    app: tauri::AppHandle,
+    db_state: State<'_, DatabaseState>
+) -> Result<TaskTimeLog, AppError> {
+    let clock = db_state.clock.clone();
+    let (log, remaining_seconds) = db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        let log = time_logs::add_time_log(&tx, &*clock, task_id, &started_at, &ended_at)?;
+        let remaining_seconds = time_logs::get_task_remaining_time(&tx, &*clock, task_id)?;
+        tx.commit()?;
+        Ok((log, remaining_seconds))
+    }).await?;
+
+    let _ = app.emit("tasks-changed", TaskChangedPayload { task_id, remaining_seconds });
+    Ok(log)
+}
+
+#[tauri::command]
+pub async fn update_time_log(
+    log_id: i64,
+    started_at: String,
+    ended_at: String,
+    app: tauri::AppHandle,
+    db_state: State<'_, DatabaseState>
+) -> Result<TaskTimeLog, AppError> {
+    let clock = db_state.clock.clone();
+    let (log, remaining_seconds) = db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        let log = time_logs::update_time_log(&tx, &*clock, log_id, &started_at, &ended_at)?;
+        let remaining_seconds = time_logs::get_task_remaining_time(&tx, &*clock, log.task_id)?;
+        tx.commit()?;
+        Ok((log, remaining_seconds))
+    }).await?;
+
+    let _ = app.emit("tasks-changed", TaskChangedPayload { task_id: log.task_id, remaining_seconds });
+    Ok(log)
+}
+
+#[tauri::command]
+pub async fn delete_time_log(log_id: i64, app: tauri::AppHandle, db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let clock = db_state.clock.clone();
+    let (task_id, remaining_seconds) = db_state.worker.call(move |conn| {
+        let tx = conn.transaction()?;
+        let task_id = time_logs::delete_time_log(&tx, log_id)?;
+        let remaining_seconds = time_logs::get_task_remaining_time(&tx, &*clock, task_id)?;
+        tx.commit()?;
+        Ok((task_id, remaining_seconds))
+    }).await?;
+
+    let _ = app.emit("tasks-changed", TaskChangedPayload { task_id, remaining_seconds });
+    Ok(())
+}
@@ -1,449 +1,66 @@
+mod commands;
+
 use std::process::Command;
 use std::thread;
 use std::time::{Duration, Instant};
 use std::sync::{Arc, Mutex};
 
 use tauri::{Manager, PhysicalSize, PhysicalPosition, Emitter};
-use tauri::State;
 use global_hotkey::{GlobalHotKeyManager, hotkey::{HotKey, Modifiers, Code}, GlobalHotKeyEvent};
-use rusqlite::{Connection, Result as SqliteResult, OptionalExtension};
-use chrono::Utc;
-use serde::{Deserialize, Serialize};
+use clockwise_core::database::{init_database, resolve_database_path};
+use clockwise_core::idle::{detect_idle_source, pause_idle_task, record_idle_return, IdleAction, IdleMonitor};
+use clockwise_core::pomodoro::{advance_pomodoro_sessions, check_and_advance_pomodoro_sessions, get_active_timers, get_session_transition};
+use clockwise_core::recovery::{mark_clean_shutdown, recover_stale_time_logs, write_heartbeat, HEARTBEAT_INTERVAL_SECONDS};
+use clockwise_core::settings::{ensure_timezone, load_idle_threshold, load_log_level, load_offline_policy, DEFAULT_LOG_LEVEL};
+use clockwise_core::system_events::{
+    resume_suspended_task, suspend_running_task, Bus, LogindMonitor, SuspendAction, SuspendPending, SuspendTracker,
+    SystemEventSource,
+};
+use clockwise_core::time_logs::get_task_remaining_time;
+use clockwise_core::{AppError, Clock, DbWorker, OfflinePolicy, SystemClock, TaskChangedPayload};
+
+use commands::{DatabaseState, RecoveryState};
 
 // Estado compartilhado para controlar se está colapsado
 static COLLAPSED_STATE: Mutex<bool> = Mutex::new(false);
+
 // Último tempo que o atalho foi executado (para debounce)
 static LAST_HOTKEY_TIME: Mutex<Option<Instant>> = Mutex::new(None);
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct Task {
-    id: Option<i64>,
-    name: String,
-    user: String,
-    estimated_hours: f64,
-    scheduled_date: String,
-    status: String,
-    created_at: String,
-    started_at: Option<String>,
-    completed_at: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct TaskTimeLog {
-    id: Option<i64>,
-    task_id: i64,
-    started_at: String,
-    ended_at: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct PomodoroSession {
-    id: Option<i64>,
-    task_id: i64,
-    session_number: i32,
-    session_type: String, // "work" or "break"
-    duration_seconds: i32,
-    created_at: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct ActiveSession {
-    task_id: i64,
-    pomodoro_id: i64,
-    started_at: String,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct TaskWithActiveSession {
-    id: Option<i64>,
-    name: String,
-    user: String,
-    estimated_hours: f64,
-    scheduled_date: String,
-    status: String,
-    created_at: String,
-    started_at: Option<String>,
-    completed_at: Option<String>,
-    active_session: Option<ActiveSessionInfo>,
-    pomodoro_sessions: Vec<PomodoroSessionInfo>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct PomodoroSessionInfo {
-    id: Option<i64>,
-    session_number: i32,
-    session_type: String,
-    duration_seconds: i32,
-    created_at: String,
-    is_active: bool,
-    started_at: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct ActiveSessionInfo {
-    session_type: String,
-    started_at: String,
-    ends_at: String,
-    duration_seconds: i32,
-}
-
-struct DatabaseState {
-    connection: Arc<Mutex<Connection>>,
-}
-
-fn init_database() -> SqliteResult<Connection> {
-    let conn = Connection::open("tasks.db")?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS tasks (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            user TEXT NOT NULL,
-            estimated_hours REAL NOT NULL,
-            scheduled_date TEXT NOT NULL,
-            status TEXT NOT NULL DEFAULT 'pending',
-            created_at TEXT NOT NULL,
-            started_at TEXT,
-            completed_at TEXT
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS task_time_logs (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            task_id INTEGER NOT NULL,
-            started_at TEXT NOT NULL,
-            ended_at TEXT NULL,
-            FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS pomodoro_sessions (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            task_id INTEGER NOT NULL,
-            session_number INTEGER NOT NULL,
-            session_type TEXT NOT NULL CHECK (session_type IN ('work', 'break')),
-            duration_seconds INTEGER NOT NULL,
-            created_at TEXT NOT NULL,
-            FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
-        )",
-        [],
-    )?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS active_sessions (
-            task_id INTEGER PRIMARY KEY,
-            pomodoro_id INTEGER NOT NULL,
-            started_at TEXT NOT NULL,
-            FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE,
-            FOREIGN KEY (pomodoro_id) REFERENCES pomodoro_sessions (id) ON DELETE CASCADE
-        )",
-        [],
-    )?;
-
-    Ok(conn)
-}
-
-fn debug_task_time_logs(conn: &Connection, task_id: i64) -> Result<(), rusqlite::Error> {
-    let mut stmt = conn.prepare(
-        "SELECT id, started_at, ended_at FROM task_time_logs WHERE task_id = ?1 ORDER BY started_at"
-    )?;
-
-    let log_iter = stmt.query_map([task_id], |row| {
-        Ok((
-            row.get::<_, i64>(0)?, // id
-            row.get::<_, String>(1)?, // started_at
-            row.get::<_, Option<String>>(2)?, // ended_at
-        ))
-    })?;
-
-    println!("🔍 Logs de tempo para tarefa {}:", task_id);
-    for log_result in log_iter {
-        let (log_id, started_at, ended_at) = log_result?;
-        match ended_at {
-            Some(ended) => println!("  📝 Log {}: {} → {} (finalizado)", log_id, started_at, ended),
-            None => println!("  ⏳ Log {}: {} → (ativo)", log_id, started_at),
-        }
-    }
-
-    Ok(())
-}
-
-fn calculate_task_remaining_time(conn: &Connection, task_id: i64, estimated_hours: f64) -> Result<i64, rusqlite::Error> {
-    let mut stmt = conn.prepare(
-        "SELECT started_at, ended_at FROM task_time_logs WHERE task_id = ?1 ORDER BY started_at"
-    )?;
-
-    let log_iter = stmt.query_map([task_id], |row| {
-        Ok((
-            row.get::<_, String>(0)?, // started_at
-            row.get::<_, Option<String>>(1)?, // ended_at
-        ))
-    })?;
-
-    let mut total_seconds_worked = 0i64;
-    let now = Utc::now();
-
-    println!("🔍 Calculando tempo para tarefa {}: estimated_hours = {}", task_id, estimated_hours);
-
-    for log_result in log_iter {
-        let (started_at_str, ended_at_opt) = log_result?;
-
-        let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
-            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "started_at".to_string(), rusqlite::types::Type::Text))?;
-
-        let ended_at = match ended_at_opt {
-            Some(ended_at_str) => {
-                let ended_time = chrono::DateTime::parse_from_rfc3339(&ended_at_str)
-                    .map_err(|_| rusqlite::Error::InvalidColumnType(1, "ended_at".to_string(), rusqlite::types::Type::Text))?;
-                println!("📝 Log completo: {} → {} (finalizado)", started_at_str, ended_at_str);
-                ended_time
-            }
-            None => {
-                println!("⏳ Log ativo: {} → agora (em andamento)", started_at_str);
-                now.into() // Se não tem ended_at, significa que está ativo, usa tempo atual
-            }
-        };
-
-        let duration = ended_at.signed_duration_since(started_at);
-        let duration_seconds = duration.num_seconds();
-        total_seconds_worked += duration_seconds;
-
-        println!("⏱️ Duração deste período: {}s", duration_seconds);
-    }
-
-    let estimated_seconds = (estimated_hours * 3600.0) as i64;
-    let remaining_seconds = estimated_seconds - total_seconds_worked;
-
-    println!("📊 Total trabalhado: {}s, Estimado: {}s, Restante: {}s",
-             total_seconds_worked, estimated_seconds, remaining_seconds);
-
-    Ok(remaining_seconds)
-}
-
-fn create_pomodoro_cycles(conn: &Connection, task_id: i64) -> Result<(), rusqlite::Error> {
-    // Criar ciclo padrão Pomodoro: 25min trabalho, 5min pausa, repetir 4x, depois 15min pausa longa
-    let cycles = [
-        ("work", 25 * 60),    // 25 min trabalho
-        ("break", 5 * 60),    // 5 min pausa
-        ("work", 25 * 60),    // 25 min trabalho
-        ("break", 5 * 60),    // 5 min pausa
-        ("work", 25 * 60),    // 25 min trabalho
-        ("break", 5 * 60),    // 5 min pausa
-        ("work", 25 * 60),    // 25 min trabalho
-        ("break", 15 * 60),   // 15 min pausa longa
-    ];
-
-    let now = Utc::now().to_rfc3339();
-
-    for (i, (session_type, duration)) in cycles.iter().enumerate() {
-        conn.execute(
-            "INSERT INTO pomodoro_sessions (task_id, session_number, session_type, duration_seconds, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            [
-                &task_id.to_string(),
-                &(i + 1).to_string(),
-                &session_type.to_string(),
-                &duration.to_string(),
-                &now
-            ],
-        )?;
-    }
-
-    Ok(())
-}
-
-fn get_next_pomodoro_session(conn: &Connection, task_id: i64) -> Result<Option<PomodoroSession>, rusqlite::Error> {
-    // Verificar se já existem sessões para esta tarefa
-    let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM pomodoro_sessions WHERE task_id = ?1",
-        [task_id],
-        |row| row.get(0),
-    )?;
-
-    // Se não existem sessões, criar os ciclos
-    if count == 0 {
-        create_pomodoro_cycles(conn, task_id)?;
-    }
-
-        // Buscar a próxima sessão disponível (menor session_number que não está em uso)
-    let mut stmt = conn.prepare(
-        "SELECT ps.id, ps.task_id, ps.session_number, ps.session_type, ps.duration_seconds, ps.created_at
-         FROM pomodoro_sessions ps
-         WHERE ps.task_id = ?1 AND ps.id NOT IN (
-             SELECT DISTINCT pomodoro_id FROM active_sessions WHERE task_id = ?1
-         )
-         ORDER BY ps.session_number ASC
-         LIMIT 1"
-    )?;
-
-    let session_opt = stmt.query_row([task_id], |row| {
-        Ok(PomodoroSession {
-            id: Some(row.get(0)?),
-            task_id: row.get(1)?,
-            session_number: row.get(2)?,
-            session_type: row.get(3)?,
-            duration_seconds: row.get(4)?,
-            created_at: row.get(5)?,
-        })
-    }).optional()?;
-
-    Ok(session_opt)
-}
-
-fn start_pomodoro_session(conn: &Connection, task_id: i64, pomodoro_session: &PomodoroSession) -> Result<String, rusqlite::Error> {
-    let now = Utc::now().to_rfc3339();
-
-    // Inserir sessão ativa
-    conn.execute(
-        "INSERT OR REPLACE INTO active_sessions (task_id, pomodoro_id, started_at) VALUES (?1, ?2, ?3)",
-        [&task_id.to_string(), &pomodoro_session.id.unwrap().to_string(), &now],
-    )?;
-
-    // Determinar status com base no tipo de sessão
-    let status = match pomodoro_session.session_type.as_str() {
-        "work" => "in_progress",
-        "break" => "waiting",
-        _ => "in_progress",
-    };
-
-    // Atualizar status da tarefa
-    conn.execute(
-        "UPDATE tasks SET status = ?1 WHERE id = ?2",
-        [status, &task_id.to_string()],
-    )?;
-
-    Ok(status.to_string())
-}
-
-fn check_and_advance_pomodoro_sessions(conn: &Connection) -> Result<Vec<i64>, rusqlite::Error> {
-    let now = Utc::now();
-    let mut advanced_tasks = Vec::new();
-
-    // Buscar sessões ativas que ultrapassaram o tempo
-    let mut stmt = conn.prepare(
-        "SELECT a.task_id, a.pomodoro_id, a.started_at, p.duration_seconds, p.session_type
-         FROM active_sessions a
-         JOIN pomodoro_sessions p ON a.pomodoro_id = p.id"
-    )?;
-
-    let rows: Vec<(i64, i64, String, i32, String)> = stmt.query_map([], |row| {
-        Ok((
-            row.get(0)?, // task_id
-            row.get(1)?, // pomodoro_id
-            row.get(2)?, // started_at
-            row.get(3)?, // duration_seconds
-            row.get(4)?, // session_type
-        ))
-    })?.collect::<Result<Vec<_>, _>>()?;
-
-    for (task_id, pomodoro_id, started_at_str, duration_seconds, session_type) in rows {
-        let started_at = chrono::DateTime::parse_from_rfc3339(&started_at_str)
-            .map_err(|_| rusqlite::Error::InvalidColumnType(0, "started_at".to_string(), rusqlite::types::Type::Text))?;
-
-        let elapsed = now.signed_duration_since(started_at);
-        let elapsed_seconds = elapsed.num_seconds();
-
-        // Se ultrapassou o tempo da sessão
-        if elapsed_seconds >= duration_seconds as i64 {
-            println!("Sessão {} da tarefa {} ultrapassou tempo: {}s >= {}s",
-                pomodoro_id, task_id, elapsed_seconds, duration_seconds);
-
-            // Remover sessão ativa atual
-            conn.execute(
-                "DELETE FROM active_sessions WHERE task_id = ?1",
-                [task_id],
-            )?;
-
-            // Finalizar log de tempo se for sessão de trabalho E se ainda não foi finalizado
-            if session_type == "work" {
-                // Verificar se há log ativo (não finalizado) para esta tarefa
-                let active_log_count: i64 = conn.query_row(
-                    "SELECT COUNT(*) FROM task_time_logs WHERE task_id = ?1 AND ended_at IS NULL",
-                    [task_id],
-                    |row| row.get(0),
-                )?;
-
-                // Só finalizar se realmente há um log ativo (não foi pausado manualmente)
-                if active_log_count > 0 {
-                    let session_end_time = started_at + chrono::Duration::seconds(duration_seconds as i64);
-                    conn.execute(
-                        "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
-                        [&session_end_time.to_rfc3339(), &task_id.to_string()],
-                    )?;
-                    println!("🕐 Log de tempo finalizado automaticamente para tarefa {} às {}", task_id, session_end_time.to_rfc3339());
-                } else {
-                    println!("⚠️ Log já foi finalizado manualmente para tarefa {}, não sobrescrever", task_id);
-                }
-            }
-
-            // Buscar próxima sessão
-            let next_session = get_next_pomodoro_session(conn, task_id)?;
-
-            match next_session {
-                Some(next_pomodoro) => {
-                    // Iniciar próxima sessão automaticamente
-                    start_pomodoro_session(conn, task_id, &next_pomodoro)?;
-                    advanced_tasks.push(task_id);
-
-                    println!("Tarefa {} avançou para sessão: {} ({})",
-                        task_id, next_pomodoro.session_type, next_pomodoro.session_number);
-                }
-                None => {
-                    // Não há mais sessões, completar tarefa
-                    let now_str = now.to_rfc3339();
-                    conn.execute(
-                        "UPDATE tasks SET status = 'completed', completed_at = ?1 WHERE id = ?2",
-                        [&now_str, &task_id.to_string()],
-                    )?;
-                    advanced_tasks.push(task_id);
-
-                    println!("Tarefa {} completada automaticamente - todos os Pomodoros finalizados", task_id);
-                }
-            }
-        }
-    }
-
-    Ok(advanced_tasks)
-}
-
 #[tauri::command]
-async fn toggle_collapse(window: tauri::WebviewWindow, is_collapsed: bool) -> Result<(), String> {
-    println!("🔧 toggle_collapse chamado com is_collapsed: {}", is_collapsed);
+async fn toggle_collapse(window: tauri::WebviewWindow, is_collapsed: bool) -> Result<(), AppError> {
+    log::debug!(target: "window", "toggle_collapse chamado com is_collapsed: {}", is_collapsed);
 
     // Atualizar o estado global
     if let Ok(mut state) = COLLAPSED_STATE.lock() {
         *state = is_collapsed;
-        println!("🔧 Estado global atualizado para: {}", *state);
+        log::debug!(target: "window", "Estado global atualizado para: {}", *state);
     }
 
     let new_height = if is_collapsed { 1 } else { 55 };
-    println!("🔧 Tentando redimensionar janela para altura: {}", new_height);
+    log::debug!(target: "window", "Tentando redimensionar janela para altura: {}", new_height);
 
     // Primeiro, tentar redimensionar
     match window.set_size(PhysicalSize::new(1920, new_height)) {
-        Ok(_) => println!("✓ Janela redimensionada com sucesso para {}px", new_height),
+        Ok(_) => log::debug!(target: "window", "Janela redimensionada com sucesso para {}px", new_height),
         Err(e) => {
-            println!("✗ Erro ao redimensionar janela: {}", e);
-            return Err(e.to_string());
+            log::error!(target: "window", "Erro ao redimensionar janela: {}", e);
+            return Err(AppError::Window(e.to_string()));
         }
     }
 
     // Se colapsada, também mover para posição específica
     if is_collapsed {
-        println!("🔧 Movendo janela colapsada para posição (-1, -1)");
+        log::debug!(target: "window", "Movendo janela colapsada para posição (-1, -1)");
         match window.set_position(PhysicalPosition::new(-1, -1)) {
-            Ok(_) => println!("✓ Janela movida para posição colapsada"),
-            Err(e) => println!("✗ Erro ao mover janela: {}", e),
+            Ok(_) => log::debug!(target: "window", "Janela movida para posição colapsada"),
+            Err(e) => log::error!(target: "window", "Erro ao mover janela: {}", e),
         }
     } else {
-        println!("🔧 Restaurando janela para posição (0, 0)");
+        log::debug!(target: "window", "Restaurando janela para posição (0, 0)");
         match window.set_position(PhysicalPosition::new(0, 0)) {
-            Ok(_) => println!("✓ Janela restaurada para posição normal"),
-            Err(e) => println!("✗ Erro ao restaurar janela: {}", e),
+            Ok(_) => log::debug!(target: "window", "Janela restaurada para posição normal"),
+            Err(e) => log::error!(target: "window", "Erro ao restaurar janela: {}", e),
         }
     }
 
@@ -451,26 +68,26 @@ async fn toggle_collapse(window: tauri::WebviewWindow, is_collapsed: bool) -> Re
     thread::sleep(Duration::from_millis(100));
 
     match window.inner_size() {
-        Ok(size) => println!("🔧 Tamanho atual da janela: {}x{}", size.width, size.height),
-        Err(e) => println!("✗ Erro ao obter tamanho da janela: {}", e),
+        Ok(size) => log::debug!(target: "window", "Tamanho atual da janela: {}x{}", size.width, size.height),
+        Err(e) => log::error!(target: "window", "Erro ao obter tamanho da janela: {}", e),
     }
 
     #[cfg(target_os = "linux")]
     {
-        println!("🔧 Aplicando configurações wmctrl...");
+        log::debug!(target: "window", "Aplicando configurações wmctrl...");
         thread::sleep(Duration::from_millis(200));
 
         if let Ok(output) = Command::new("wmctrl").args(["-l"]).output() {
             let window_list = String::from_utf8_lossy(&output.stdout);
-            println!("🔧 Procurando janela na lista wmctrl...");
+            log::debug!(target: "window", "Procurando janela na lista wmctrl...");
 
             for line in window_list.lines() {
                 if line.contains("ClockWise") || line.contains("app") || line.contains("Clockwise") {
                     let window_id = line.split_whitespace().next().unwrap_or("");
-                    println!("🔧 Encontrada janela ID: {} -> {}", window_id, line);
+                    log::debug!(target: "window", "Encontrada janela ID: {} -> {}", window_id, line);
 
                     if is_collapsed {
-                        println!("🔧 Aplicando configuração colapsada via wmctrl");
+                        log::debug!(target: "window", "Aplicando configuração colapsada via wmctrl");
                         // Tentar diferentes abordagens para colapsar
                         let commands = vec![
                             vec!["-i", "-r", window_id, "-e", "0,0,-50,1920,1"],
@@ -480,12 +97,12 @@ async fn toggle_collapse(window: tauri::WebviewWindow, is_collapsed: bool) -> Re
 
                         for cmd in commands {
                             match Command::new("wmctrl").args(&cmd).output() {
-                                Ok(_) => println!("✓ Comando wmctrl executado: {:?}", cmd),
-                                Err(e) => println!("✗ Erro no comando wmctrl {:?}: {}", cmd, e),
+                                Ok(_) => log::debug!(target: "window", "Comando wmctrl executado: {:?}", cmd),
+                                Err(e) => log::error!(target: "window", "Erro no comando wmctrl {:?}: {}", cmd, e),
                             }
                         }
                     } else {
-                        println!("🔧 Aplicando configuração expandida via wmctrl");
+                        log::debug!(target: "window", "Aplicando configuração expandida via wmctrl");
                         let commands = vec![
                             vec!["-i", "-r", window_id, "-b", "remove,hidden"],
                             vec!["-i", "-r", window_id, "-e", "0,0,0,1920,55"],
@@ -494,8 +111,8 @@ async fn toggle_collapse(window: tauri::WebviewWindow, is_collapsed: bool) -> Re
 
                         for cmd in commands {
                             match Command::new("wmctrl").args(&cmd).output() {
-                                Ok(_) => println!("✓ Comando wmctrl executado: {:?}", cmd),
-                                Err(e) => println!("✗ Erro no comando wmctrl {:?}: {}", cmd, e),
+                                Ok(_) => log::debug!(target: "window", "Comando wmctrl executado: {:?}", cmd),
+                                Err(e) => log::error!(target: "window", "Erro no comando wmctrl {:?}: {}", cmd, e),
                             }
                         }
                     }
@@ -503,26 +120,25 @@ async fn toggle_collapse(window: tauri::WebviewWindow, is_collapsed: bool) -> Re
                 }
             }
         } else {
-            println!("✗ wmctrl não disponível");
+            log::warn!(target: "window", "wmctrl não disponível");
         }
     }
 
-    println!("🔧 toggle_collapse finalizado");
+    log::debug!(target: "window", "toggle_collapse finalizado");
     Ok(())
 }
 
 #[tauri::command]
-async fn get_collapsed_state() -> Result<bool, String> {
+async fn get_collapsed_state() -> Result<bool, AppError> {
     let state = COLLAPSED_STATE.lock()
-        .map(|state| *state)
-        .map_err(|e| e.to_string())?;
-    println!("🔧 get_collapsed_state retornando: {}", state);
+        .map(|state| *state)?;
+    log::debug!(target: "window", "get_collapsed_state retornando: {}", state);
     Ok(state)
 }
 
 #[tauri::command]
-async fn test_hotkey_manually(window: tauri::WebviewWindow) -> Result<(), String> {
-    println!("🔧 Teste manual do atalho executado!");
+async fn test_hotkey_manually(window: tauri::WebviewWindow) -> Result<(), AppError> {
+    log::debug!(target: "hotkey", "Teste manual do atalho executado!");
 
     // Alternar estado manualmente
     let new_state = if let Ok(state) = COLLAPSED_STATE.lock() {
@@ -531,7 +147,7 @@ async fn test_hotkey_manually(window: tauri::WebviewWindow) -> Result<(), String
         false
     };
 
-    println!("🔧 Novo estado será: {}", new_state);
+    log::debug!(target: "hotkey", "Novo estado será: {}", new_state);
 
     // Chamar toggle_collapse para aplicar as mudanças
     toggle_collapse(window, new_state).await?;
@@ -546,7 +162,7 @@ fn should_process_hotkey() -> bool {
         if let Some(last) = *last_time {
             // Se passou menos de 500ms desde o último atalho, ignorar
             if now.duration_since(last) < Duration::from_millis(500) {
-                println!("🔧 Atalho ignorado (debounce)");
+                log::debug!(target: "hotkey", "Atalho ignorado (debounce)");
                 return false;
             }
         }
@@ -559,7 +175,7 @@ fn should_process_hotkey() -> bool {
 }
 
 #[tauri::command]
-async fn get_system_volume() -> Result<i32, String> {
+async fn get_system_volume() -> Result<i32, AppError> {
     #[cfg(target_os = "linux")]
     {
         // Usar pactl para obter o volume no Linux
@@ -596,7 +212,7 @@ async fn get_system_volume() -> Result<i32, String> {
 }
 
 #[tauri::command]
-async fn set_system_volume(volume: i32) -> Result<(), String> {
+async fn set_system_volume(volume: i32) -> Result<(), AppError> {
     let clamped_volume = volume.clamp(0, 100);
 
     #[cfg(target_os = "linux")]
@@ -607,25 +223,25 @@ async fn set_system_volume(volume: i32) -> Result<(), String> {
             .output()
         {
             Ok(_) => {
-                println!("🔊 Volume definido para {}%", clamped_volume);
+                log::info!(target: "volume", "Volume definido para {}%", clamped_volume);
                 Ok(())
             }
             Err(e) => {
-                println!("✗ Erro ao definir volume: {}", e);
-                Err(format!("Erro ao definir volume: {}", e))
+                log::error!(target: "volume", "Erro ao definir volume: {}", e);
+                Err(AppError::System(format!("Erro ao definir volume: {}", e)))
             }
         }
     }
 
     #[cfg(not(target_os = "linux"))]
     {
-        println!("🔊 Volume seria definido para {}% (não implementado para este OS)", clamped_volume);
+        log::info!(target: "volume", "Volume seria definido para {}% (não implementado para este OS)", clamped_volume);
         Ok(())
     }
 }
 
 #[tauri::command]
-async fn get_system_mute_status() -> Result<bool, String> {
+async fn get_system_mute_status() -> Result<bool, AppError> {
     #[cfg(target_os = "linux")]
     {
         match Command::new("pactl")
@@ -647,7 +263,7 @@ async fn get_system_mute_status() -> Result<bool, String> {
 }
 
 #[tauri::command]
-async fn toggle_system_mute() -> Result<bool, String> {
+async fn toggle_system_mute() -> Result<bool, AppError> {
     #[cfg(target_os = "linux")]
     {
         match Command::new("pactl")
@@ -658,7 +274,7 @@ async fn toggle_system_mute() -> Result<bool, String> {
                 // Obter o novo status após o toggle
                 get_system_mute_status().await
             }
-            Err(e) => Err(format!("Erro ao alternar mute: {}", e))
+            Err(e) => Err(AppError::System(format!("Erro ao alternar mute: {}", e)))
         }
     }
 
@@ -669,527 +285,17 @@ async fn toggle_system_mute() -> Result<bool, String> {
 }
 
 #[tauri::command]
-async fn load_tasks(db_state: State<'_, DatabaseState>) -> Result<Vec<Task>, String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-
-    let mut stmt = conn.prepare(
-        "SELECT id, name, user, estimated_hours, scheduled_date, status, created_at, started_at, completed_at
-         FROM tasks ORDER BY scheduled_date ASC, created_at ASC"
-    ).map_err(|e| e.to_string())?;
-
-    let task_iter = stmt.query_map([], |row| {
-        Ok(Task {
-            id: Some(row.get(0)?),
-            name: row.get(1)?,
-            user: row.get(2)?,
-            estimated_hours: row.get(3)?,
-            scheduled_date: row.get(4)?,
-            status: row.get(5)?,
-            created_at: row.get(6)?,
-            started_at: row.get(7)?,
-            completed_at: row.get(8)?,
-        })
-    }).map_err(|e| e.to_string())?;
-
-    let mut tasks = Vec::new();
-    for task in task_iter {
-        tasks.push(task.map_err(|e| e.to_string())?);
-    }
-
-    Ok(tasks)
-}
-
-#[tauri::command]
-async fn add_task(
-    name: String,
-    user: String,
-    estimated_hours: f64,
-    scheduled_date: String,
-    db_state: State<'_, DatabaseState>
-) -> Result<Task, String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-    let now = Utc::now().to_rfc3339();
-
-    conn.execute(
-        "INSERT INTO tasks (name, user, estimated_hours, scheduled_date, status, created_at)
-         VALUES (?1, ?2, ?3, ?4, 'pending', ?5)",
-        [&name, &user, &estimated_hours.to_string(), &scheduled_date, &now],
-    ).map_err(|e| e.to_string())?;
-
-    let id = conn.last_insert_rowid();
-
-    // Criar sessões Pomodoro automaticamente quando a tarefa é criada
-    create_pomodoro_cycles(&conn, id).map_err(|e| e.to_string())?;
-    println!("🍅 Sessões Pomodoro criadas automaticamente para tarefa {}", id);
-
-    Ok(Task {
-        id: Some(id),
-        name,
-        user,
-        estimated_hours,
-        scheduled_date,
-        status: "pending".to_string(),
-        created_at: now,
-        started_at: None,
-        completed_at: None,
-    })
-}
-
-#[tauri::command]
-async fn start_task(task_id: i64, stop_and_start: Option<bool>, db_state: State<'_, DatabaseState>) -> Result<(), String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-    let now = Utc::now().to_rfc3339();
-    println!("🔧 Iniciando tarefa: {}", task_id);
-    // Verificar se já existe uma tarefa ativa (sem ended_at)
-    let mut stmt = conn.prepare(
-        "SELECT COUNT(*) FROM task_time_logs WHERE task_id = ?1 AND ended_at IS NULL"
-    ).map_err(|e| e.to_string())?;
-
-    let count: i64 = stmt.query_row([task_id], |row| row.get(0)).map_err(|e| e.to_string())?;
-
-    if count > 0 {
-        return Err("Tarefa já está ativa".to_string());
-    }
-
-    // NOVA REGRA: Verificar se há alguma outra tarefa em andamento
-    let active_tasks_count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM tasks WHERE status IN ('in_progress', 'waiting') AND id != ?1",
-        [task_id],
-        |row| row.get(0),
-    ).map_err(|e| e.to_string())?;
-
-    // Se stop_and_start for true, pausar automaticamente tarefas ativas
-    if active_tasks_count > 0 {
-        if stop_and_start.unwrap_or(false) {
-            println!("🔄 stop_and_start=true: pausando tarefas ativas automaticamente");
-
-            // Buscar IDs das tarefas ativas
-            let mut stmt = conn.prepare(
-                "SELECT id FROM tasks WHERE status IN ('in_progress', 'waiting') AND id != ?1"
-            ).map_err(|e| e.to_string())?;
-
-            let active_task_ids: Vec<i64> = stmt.query_map([task_id], |row| {
-                Ok(row.get::<_, i64>(0)?)
-            }).map_err(|e| e.to_string())?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| e.to_string())?;
-
-            // Pausar cada tarefa ativa
-            for active_task_id in active_task_ids {
-                println!("🛑 Pausando tarefa ativa: {}", active_task_id);
-
-                // Remover sessão ativa
-                conn.execute(
-                    "DELETE FROM active_sessions WHERE task_id = ?1",
-                    [active_task_id],
-                ).map_err(|e| e.to_string())?;
-
-                // Finalizar log de tempo se existir
-                conn.execute(
-                    "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
-                    [&now, &active_task_id.to_string()],
-                ).map_err(|e| e.to_string())?;
-
-                // Atualizar status para 'paused'
-                conn.execute(
-                    "UPDATE tasks SET status = 'paused' WHERE id = ?1",
-                    [&active_task_id.to_string()],
-                ).map_err(|e| e.to_string())?;
-
-                println!("✅ Tarefa {} pausada automaticamente", active_task_id);
-            }
-        } else {
-            return Err("Apenas uma tarefa pode estar em andamento por vez. Pause a tarefa atual primeiro.".to_string());
-        }
-    }
-
-    // Buscar próxima sessão Pomodoro
-    let next_session = get_next_pomodoro_session(&conn, task_id)
-        .map_err(|e| e.to_string())?;
-
-        match &next_session {
-        Some(pomodoro_session) => {
-            // Iniciar sessão Pomodoro
-            let status = start_pomodoro_session(&conn, task_id, pomodoro_session)
-                .map_err(|e| e.to_string())?;
-
-            // Atualizar started_at apenas se for a primeira vez
-            let mut stmt = conn.prepare("SELECT started_at FROM tasks WHERE id = ?1")
-                .map_err(|e| e.to_string())?;
-            let current_started_at: Option<String> = stmt.query_row([task_id], |row| row.get(0))
-                .map_err(|e| e.to_string())?;
-
-            if current_started_at.is_none() {
-                conn.execute(
-                    "UPDATE tasks SET started_at = ?1 WHERE id = ?2",
-                    [&now, &task_id.to_string()],
-                ).map_err(|e| e.to_string())?;
-            }
-
-            println!("Tarefa {} iniciada com sessão Pomodoro: {} ({})",
-                task_id, pomodoro_session.session_type, status);
-        }
-        None => {
-            // Não há mais sessões Pomodoro, marcar como completada
-            conn.execute(
-                "UPDATE tasks SET status = 'completed', completed_at = ?1 WHERE id = ?2",
-                [&now, &task_id.to_string()],
-            ).map_err(|e| e.to_string())?;
-
-            println!("Tarefa {} completada - todos os ciclos Pomodoro finalizados", task_id);
-        }
-    }
-
-    // Criar novo log de tempo (apenas para sessões de trabalho)
-    if let Some(session) = &next_session {
-        if session.session_type == "work" {
-            conn.execute(
-                "INSERT INTO task_time_logs (task_id, started_at) VALUES (?1, ?2)",
-                [&task_id.to_string(), &now],
-            ).map_err(|e| e.to_string())?;
-        }
-    }
-
-    Ok(())
-}
-
-#[tauri::command]
-async fn check_pomodoro_sessions(db_state: State<'_, DatabaseState>) -> Result<Vec<i64>, String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-
-    let advanced_tasks = check_and_advance_pomodoro_sessions(&conn)
-        .map_err(|e| e.to_string())?;
-
-    Ok(advanced_tasks)
-}
-
-#[tauri::command]
-async fn load_tasks_with_sessions(db_state: State<'_, DatabaseState>) -> Result<Vec<TaskWithActiveSession>, String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-
-    let mut stmt = conn.prepare(
-        "SELECT t.id, t.name, t.user, t.estimated_hours, t.scheduled_date, t.status,
-                t.created_at, t.started_at, t.completed_at,
-                a.started_at as session_started_at, p.session_type, p.duration_seconds
-         FROM tasks t
-         LEFT JOIN active_sessions a ON t.id = a.task_id
-         LEFT JOIN pomodoro_sessions p ON a.pomodoro_id = p.id
-         ORDER BY
-            CASE
-                WHEN t.status IN ('in_progress', 'waiting') THEN 0
-                ELSE 1
-            END ASC,
-            t.scheduled_date ASC,
-            t.created_at ASC"
-    ).map_err(|e| e.to_string())?;
-
-    let task_iter = stmt.query_map([], |row| {
-        let task_id: i64 = row.get(0)?;
-        let session_started_at: Option<String> = row.get(9)?;
-        let session_type: Option<String> = row.get(10)?;
-        let duration_seconds: Option<i32> = row.get(11)?;
-
-        let active_session = if let (Some(started_at), Some(s_type), Some(duration)) =
-            (session_started_at, session_type, duration_seconds) {
-
-            let started_time = chrono::DateTime::parse_from_rfc3339(&started_at)
-                .map_err(|_| rusqlite::Error::InvalidColumnType(9, "session_started_at".to_string(), rusqlite::types::Type::Text))?;
-            let ends_at = started_time + chrono::Duration::seconds(duration as i64);
-
-            Some(ActiveSessionInfo {
-                session_type: s_type,
-                started_at,
-                ends_at: ends_at.to_rfc3339(),
-                duration_seconds: duration,
-            })
-        } else {
-            None
-        };
-
-        Ok(TaskWithActiveSession {
-            id: Some(task_id),
-            name: row.get(1)?,
-            user: row.get(2)?,
-            estimated_hours: row.get(3)?,
-            scheduled_date: row.get(4)?,
-            status: row.get(5)?,
-            created_at: row.get(6)?,
-            started_at: row.get(7)?,
-            completed_at: row.get(8)?,
-            active_session,
-            pomodoro_sessions: Vec::new(), // Será preenchido depois
-        })
-    }).map_err(|e| e.to_string())?;
-
-    let mut tasks = Vec::new();
-    for task in task_iter {
-        tasks.push(task.map_err(|e| e.to_string())?);
-    }
-
-    // Agora, carregar todas as sessões Pomodoro para cada task
-    for task in &mut tasks {
-        if let Some(task_id) = task.id {
-            let mut pomodoro_stmt = conn.prepare(
-                "SELECT ps.id, ps.session_number, ps.session_type, ps.duration_seconds, ps.created_at,
-                        a.started_at as active_started_at
-                 FROM pomodoro_sessions ps
-                 LEFT JOIN active_sessions a ON ps.id = a.pomodoro_id AND ps.task_id = a.task_id
-                 WHERE ps.task_id = ?1
-                 ORDER BY ps.session_number ASC"
-            ).map_err(|e| e.to_string())?;
-
-            let pomodoro_iter = pomodoro_stmt.query_map([task_id], |row| {
-                let active_started_at: Option<String> = row.get(5)?;
-                Ok(PomodoroSessionInfo {
-                    id: Some(row.get(0)?),
-                    session_number: row.get(1)?,
-                    session_type: row.get(2)?,
-                    duration_seconds: row.get(3)?,
-                    created_at: row.get(4)?,
-                    is_active: active_started_at.is_some(),
-                    started_at: active_started_at,
-                })
-            }).map_err(|e| e.to_string())?;
-
-                        let mut pomodoro_sessions = Vec::new();
-            for session in pomodoro_iter {
-                pomodoro_sessions.push(session.map_err(|e| e.to_string())?);
-            }
-
-            println!("🍅 Tarefa {} ({}) carregou {} sessões Pomodoro",
-                task_id, task.name, pomodoro_sessions.len());
-
-            task.pomodoro_sessions = pomodoro_sessions;
-        }
-    }
-
-    Ok(tasks)
-}
-
-#[tauri::command]
-async fn complete_task(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-    let now = Utc::now().to_rfc3339();
-
-    // Remover sessão ativa se existir
-    conn.execute(
-        "DELETE FROM active_sessions WHERE task_id = ?1",
-        [task_id],
-    ).map_err(|e| e.to_string())?;
-
-    // Finalizar log ativo se existir
-    conn.execute(
-        "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
-        [&now, &task_id.to_string()],
-    ).map_err(|e| e.to_string())?;
-
-    // Atualizar status da tarefa
-    conn.execute(
-        "UPDATE tasks SET status = 'completed', completed_at = ?1 WHERE id = ?2",
-        [&now, &task_id.to_string()],
-    ).map_err(|e| e.to_string())?;
-
-    println!("Tarefa {} completada manualmente", task_id);
-    Ok(())
-}
-
-#[tauri::command]
-async fn pause_task(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-    let now = Utc::now().to_rfc3339();
-
-    // Verificar se há sessão ativa
-    let active_session_exists: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM active_sessions WHERE task_id = ?1",
-        [task_id],
-        |row| row.get(0),
-    ).map_err(|e| e.to_string())?;
-
-    if active_session_exists == 0 {
-        return Err("Nenhuma sessão Pomodoro ativa encontrada para pausar".to_string());
-    }
-
-    // Finalizar log de tempo ANTES de remover sessão ativa (para evitar conflito com check_and_advance)
-    let rows_updated = conn.execute(
-        "UPDATE task_time_logs SET ended_at = ?1 WHERE task_id = ?2 AND ended_at IS NULL",
-        [&now, &task_id.to_string()],
-    ).map_err(|e| e.to_string())?;
-
-    println!("⏸️ Pausando tarefa {} às {} - {} logs finalizados", task_id, now, rows_updated);
-
-    // Debug: mostrar logs após pausar
-    let _ = debug_task_time_logs(&conn, task_id);
-
-    // Remover sessão ativa (pausa o Pomodoro) - fazer isso por último
-    conn.execute(
-        "DELETE FROM active_sessions WHERE task_id = ?1",
-        [task_id],
-    ).map_err(|e| e.to_string())?;
-
-    // Atualizar status da tarefa para 'paused'
-    conn.execute(
-        "UPDATE tasks SET status = 'paused' WHERE id = ?1",
-        [&task_id.to_string()],
-    ).map_err(|e| e.to_string())?;
-
-    println!("Tarefa {} pausada - sessão Pomodoro interrompida", task_id);
-    Ok(())
-}
-
-#[tauri::command]
-async fn resume_task(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-
-    // Verificar se a tarefa existe e está pausada
-    let mut stmt = conn.prepare(
-        "SELECT status FROM tasks WHERE id = ?1"
-    ).map_err(|e| e.to_string())?;
-
-    let status: String = stmt.query_row([task_id], |row| row.get(0))
-        .map_err(|_| "Tarefa não encontrada".to_string())?;
-
-    if status != "paused" {
-        return Err("Tarefa não está pausada".to_string());
-    }
-
-    // NOVA REGRA: Verificar se há alguma outra tarefa em andamento
-    let active_tasks_count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM tasks WHERE status IN ('in_progress', 'waiting') AND id != ?1",
-        [task_id],
-        |row| row.get(0),
-    ).map_err(|e| e.to_string())?;
-
-    if active_tasks_count > 0 {
-        return Err("Apenas uma tarefa pode estar em andamento por vez. Pause a tarefa atual primeiro.".to_string());
-    }
-
-    // Verificar se não há sessão ativa
-    let active_session_exists: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM active_sessions WHERE task_id = ?1",
-        [task_id],
-        |row| row.get(0),
-    ).map_err(|e| e.to_string())?;
-
-    if active_session_exists > 0 {
-        return Err("Tarefa já tem uma sessão Pomodoro ativa".to_string());
-    }
-
-    // Buscar próxima sessão Pomodoro (a mesma lógica de start_task)
-    let next_session = get_next_pomodoro_session(&conn, task_id)
-        .map_err(|e| e.to_string())?;
-
-    match next_session {
-        Some(pomodoro_session) => {
-            // Retomar com próxima sessão Pomodoro
-            let status = start_pomodoro_session(&conn, task_id, &pomodoro_session)
-                .map_err(|e| e.to_string())?;
-
-            // Criar novo log de tempo apenas para sessões de trabalho
-            if pomodoro_session.session_type == "work" {
-                let now = Utc::now().to_rfc3339();
-                conn.execute(
-                    "INSERT INTO task_time_logs (task_id, started_at) VALUES (?1, ?2)",
-                    [&task_id.to_string(), &now],
-                ).map_err(|e| e.to_string())?;
-                println!("▶️ Retomando tarefa {} às {} - novo log criado", task_id, now);
-
-                // Debug: mostrar logs após retomar
-                let _ = debug_task_time_logs(&conn, task_id);
-            }
-
-            println!("Tarefa {} retomada com sessão Pomodoro: {} ({})",
-                task_id, pomodoro_session.session_type, status);
-        }
-        None => {
-            // Não há mais sessões, completar tarefa
-            let now = Utc::now().to_rfc3339();
-            conn.execute(
-                "UPDATE tasks SET status = 'completed', completed_at = ?1 WHERE id = ?2",
-                [&now, &task_id.to_string()],
-            ).map_err(|e| e.to_string())?;
-
-            println!("Tarefa {} completada ao retomar - todos os ciclos Pomodoro finalizados", task_id);
-        }
-    }
-
-    Ok(())
-}
-
-#[tauri::command]
-async fn get_task_remaining_time(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<i64, String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-
-    // Buscar estimated_hours da tarefa
-    let mut stmt = conn.prepare(
-        "SELECT estimated_hours FROM tasks WHERE id = ?1"
-    ).map_err(|e| e.to_string())?;
-
-    let estimated_hours: f64 = stmt.query_row([task_id], |row| row.get(0))
-        .map_err(|_| "Tarefa não encontrada".to_string())?;
-
-    // Calcular tempo restante
-    let remaining_seconds = calculate_task_remaining_time(&conn, task_id, estimated_hours)
-        .map_err(|e| e.to_string())?;
-
-    Ok(remaining_seconds)
-}
-
-#[tauri::command]
-async fn delete_task(task_id: i64, db_state: State<'_, DatabaseState>) -> Result<(), String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-
-    conn.execute(
-        "DELETE FROM tasks WHERE id = ?1",
-        [&task_id.to_string()],
-    ).map_err(|e| e.to_string())?;
-
-    Ok(())
-}
-
-#[tauri::command]
-async fn get_today_tasks(db_state: State<'_, DatabaseState>) -> Result<Vec<Task>, String> {
-    let conn = db_state.connection.lock().map_err(|e| e.to_string())?;
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-
-    let mut stmt = conn.prepare(
-        "SELECT id, name, user, estimated_hours, scheduled_date, status, created_at, started_at, completed_at
-         FROM tasks WHERE scheduled_date = ?1 ORDER BY created_at ASC"
-    ).map_err(|e| e.to_string())?;
-
-    let task_iter = stmt.query_map([&today], |row| {
-        Ok(Task {
-            id: Some(row.get(0)?),
-            name: row.get(1)?,
-            user: row.get(2)?,
-            estimated_hours: row.get(3)?,
-            scheduled_date: row.get(4)?,
-            status: row.get(5)?,
-            created_at: row.get(6)?,
-            started_at: row.get(7)?,
-            completed_at: row.get(8)?,
-        })
-    }).map_err(|e| e.to_string())?;
-
-    let mut tasks = Vec::new();
-    for task in task_iter {
-        tasks.push(task.map_err(|e| e.to_string())?);
-    }
-
-    Ok(tasks)
-}
-
-#[tauri::command]
-async fn expand_window_for_modal(window: tauri::WebviewWindow) -> Result<(), String> {
-    println!("🔧 Expandindo janela para modal...");
+async fn expand_window_for_modal(window: tauri::WebviewWindow) -> Result<(), AppError> {
+    log::debug!(target: "window", "Expandindo janela para modal...");
 
     match window.set_size(PhysicalSize::new(1920, 500)) {
         Ok(_) => {
-            println!("✓ Janela expandida para 500px");
+            log::debug!(target: "window", "Janela expandida para 500px");
 
             // Garantir que a janela esteja visível e na posição correta
             match window.set_position(PhysicalPosition::new(0, 0)) {
-                Ok(_) => println!("✓ Posição da janela ajustada"),
-                Err(e) => println!("✗ Erro ao ajustar posição: {}", e),
+                Ok(_) => log::debug!(target: "window", "Posição da janela ajustada"),
+                Err(e) => log::error!(target: "window", "Erro ao ajustar posição: {}", e),
             }
 
             #[cfg(target_os = "linux")]
@@ -1211,23 +317,23 @@ async fn expand_window_for_modal(window: tauri::WebviewWindow) -> Result<(), Str
             Ok(())
         },
         Err(e) => {
-            println!("✗ Erro ao expandir janela: {}", e);
-            Err(e.to_string())
+            log::error!(target: "window", "Erro ao expandir janela: {}", e);
+            Err(AppError::Window(e.to_string()))
         }
     }
 }
 
 #[tauri::command]
-async fn reset_window_size(window: tauri::WebviewWindow) -> Result<(), String> {
-    println!("🔧 Resetando tamanho da janela...");
+async fn reset_window_size(window: tauri::WebviewWindow) -> Result<(), AppError> {
+    log::debug!(target: "window", "Resetando tamanho da janela...");
 
     match window.set_size(PhysicalSize::new(1920, 55)) {
         Ok(_) => {
-            println!("✓ Janela resetada para 55px");
+            log::debug!(target: "window", "Janela resetada para 55px");
 
             match window.set_position(PhysicalPosition::new(0, 0)) {
-                Ok(_) => println!("✓ Posição da janela ajustada"),
-                Err(e) => println!("✗ Erro ao ajustar posição: {}", e),
+                Ok(_) => log::debug!(target: "window", "Posição da janela ajustada"),
+                Err(e) => log::error!(target: "window", "Erro ao ajustar posição: {}", e),
             }
 
             #[cfg(target_os = "linux")]
@@ -1248,56 +354,260 @@ async fn reset_window_size(window: tauri::WebviewWindow) -> Result<(), String> {
             Ok(())
         },
         Err(e) => {
-            println!("✗ Erro ao resetar janela: {}", e);
-            Err(e.to_string())
+            log::error!(target: "window", "Erro ao resetar janela: {}", e);
+            Err(AppError::Window(e.to_string()))
         }
     }
 }
 
+// Relógio do Pomodoro no backend: avança as sessões no prazo exato e emite o tempo restante a cada segundo.
+// Parado enquanto uma suspensão está pendente: quem fecha o log é o evento do sistema, não uma transição
+fn run_timer_engine(app: tauri::AppHandle, worker: DbWorker, clock: Arc<dyn Clock>, suspend_pending: SuspendPending) {
+    let mut last_heartbeat: Option<chrono::DateTime<chrono::Utc>> = None;
 
+    loop {
+        if suspend_pending.is_pending() {
+            thread::sleep(Duration::from_secs(1));
+            continue;
+        }
 
-fn main() {
-    println!("Iniciando aplicação ClockWise...");
+        let now = clock.now();
+        let heartbeat_due = last_heartbeat
+            .map_or(true, |last| now - last >= chrono::Duration::seconds(HEARTBEAT_INTERVAL_SECONDS));
+
+        // Thread própria: pode esperar o worker sem travar o executor dos comandos
+        let tick_clock = clock.clone();
+        let tick = worker.call(move |conn| {
+            // Prova de vida dos logs abertos, usada na recuperação se o app cair
+            if heartbeat_due {
+                if let Err(e) = write_heartbeat(conn, now) {
+                    log::error!(target: "timer", "Erro ao gravar batimento dos logs abertos: {}", e);
+                }
+            }
 
-    // Tentar configurar atalho global
-    println!("Criando GlobalHotKeyManager...");
-    let global_hotkey_manager = match GlobalHotKeyManager::new() {
-        Ok(manager) => {
-            println!("✓ GlobalHotKeyManager criado com sucesso");
-            Some(Arc::new(manager))
-        }
-        Err(e) => {
-            eprintln!("✗ Erro ao criar GlobalHotKeyManager: {}", e);
-            None
+            let advanced_tasks = check_and_advance_pomodoro_sessions(conn, &*tick_clock).unwrap_or_else(|e| {
+                log::error!(target: "timer", "Erro ao avançar sessões Pomodoro: {}", e);
+                Vec::new()
+            });
+
+            let mut transitions = Vec::new();
+            for task_id in advanced_tasks {
+                match get_session_transition(conn, task_id) {
+                    Ok(payload) => transitions.push(payload),
+                    Err(e) => log::error!(target: "timer", "Erro ao ler transição da tarefa {}: {}", task_id, e),
+                }
+            }
+
+            let timers = get_active_timers(conn, &*tick_clock).unwrap_or_else(|e| {
+                log::error!(target: "timer", "Erro ao calcular timers ativos: {}", e);
+                Vec::new()
+            });
+            Ok((transitions, timers))
+        }).wait();
+
+        let timers = match tick {
+            Ok((transitions, timers)) => {
+                if heartbeat_due {
+                    last_heartbeat = Some(now);
+                }
+                for payload in transitions {
+                    let _ = app.emit("session-transition", payload);
+                }
+                timers
+            }
+            Err(e) => {
+                log::error!(target: "timer", "Erro ao acessar banco no motor de timer: {}", e);
+                Vec::new()
+            }
+        };
+
+        if !timers.is_empty() {
+            let _ = app.emit("timer-tick", &timers);
         }
-    };
 
-    // Tentar registrar o atalho global
-    let hotkey_registered = if let Some(ref manager) = global_hotkey_manager {
-        let hotkey = HotKey::new(Some(Modifiers::ALT), Code::KeyC);
-        println!("Registrando atalho Alt+C (ID: {})...", hotkey.id());
+        // Dormir até o próximo segundo cheio ou até o prazo mais próximo, o que vier antes
+        let next_deadline_ms = timers.iter()
+            .filter_map(|timer| chrono::DateTime::parse_from_rfc3339(&timer.ends_at).ok())
+            .map(|ends_at| ends_at.signed_duration_since(clock.now()).num_milliseconds())
+            .min()
+            .unwrap_or(1000);
+        let until_next_second_ms = 1000 - (clock.now().timestamp_subsec_millis() as i64 % 1000);
+        let sleep_ms = next_deadline_ms.min(until_next_second_ms).max(10);
 
-        match manager.register(hotkey) {
-            Ok(_) => {
-                println!("✓ Atalho global Alt+C registrado com sucesso!");
-                true
+        thread::sleep(Duration::from_millis(sleep_ms as u64));
+    }
+}
+
+// Pausa a tarefa em andamento quando o usuário some e avisa o frontend quando ele volta
+fn run_idle_monitor(app: tauri::AppHandle, worker: DbWorker, clock: Arc<dyn Clock>, mut monitor: IdleMonitor) {
+    loop {
+        thread::sleep(Duration::from_secs(5));
+
+        // Limite lido a cada volta: mudanças nas configurações valem sem reiniciar
+        let threshold = match worker.call(|conn| Ok(load_idle_threshold(conn)?)).wait() {
+            Ok(Some(threshold)) => threshold,
+            Ok(None) => continue,
+            Err(e) => {
+                log::error!(target: "idle", "Erro ao ler o limite de inatividade: {}", e);
+                continue;
             }
+        };
+
+        let action = match monitor.poll(clock.now(), threshold) {
+            Ok(action) => action,
             Err(e) => {
-                eprintln!("✗ Erro ao registrar atalho global: {}", e);
-                false
+                log::warn!(target: "idle", "Erro ao ler o tempo de inatividade: {}", e);
+                continue;
+            }
+        };
+
+        match action {
+            Some(IdleAction::Pause { idle_since }) => {
+                let pause_clock = clock.clone();
+                let paused = worker.call(move |conn| {
+                    let tx = conn.transaction()?;
+                    let paused = match pause_idle_task(&tx, idle_since)? {
+                        Some(period) => {
+                            let remaining_seconds = get_task_remaining_time(&tx, &*pause_clock, period.task_id)?;
+                            Some((period, remaining_seconds))
+                        }
+                        None => None,
+                    };
+                    tx.commit()?;
+                    Ok(paused)
+                }).wait();
+
+                match paused {
+                    Ok(Some((period, remaining_seconds))) => {
+                        let _ = app.emit("tasks-changed", TaskChangedPayload { task_id: period.task_id, remaining_seconds });
+                        let _ = app.emit("idle-paused", &period);
+                        monitor.paused(period);
+                    }
+                    Ok(None) => log::debug!(target: "idle", "Inativo desde {}, sem tarefa em andamento", idle_since),
+                    Err(e) => log::error!(target: "idle", "Erro ao pausar tarefa por inatividade: {}", e),
+                }
             }
+            Some(IdleAction::Returned(period)) => {
+                log::info!(target: "idle", "Usuário voltou; tarefa {} estava pausada por inatividade", period.task_id);
+                let Some(ended_at) = period.ended_at.as_deref().and_then(|value| value.parse::<chrono::DateTime<chrono::Utc>>().ok()) else {
+                    continue;
+                };
+
+                // O período fica no banco; o frontend só pergunta e responde se o tempo conta
+                match worker.call(move |conn| record_idle_return(conn, ended_at)).wait() {
+                    Ok(Some(period)) => {
+                        let _ = app.emit("idle-returned", &period);
+                    }
+                    Ok(None) => log::debug!(target: "idle", "Período de inatividade já resolvido"),
+                    Err(e) => log::error!(target: "idle", "Erro ao registrar a volta do usuário: {}", e),
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+// Suspensão, bloqueio e desligamento: fecha o log no instante do sinal e retoma quando o usuário volta.
+// O inibidor do logind só é solto depois da gravação, para o sistema não dormir antes dela
+fn run_system_events(
+    app: tauri::AppHandle,
+    worker: DbWorker,
+    clock: Arc<dyn Clock>,
+    suspend_pending: SuspendPending,
+    mut source: Box<dyn SystemEventSource>,
+) {
+    let mut tracker = SuspendTracker::default();
+
+    loop {
+        let event = match source.next_event() {
+            Ok(Some(event)) => event,
+            Ok(None) => {
+                log::warn!(target: "system", "Monitor de eventos do logind encerrado");
+                return;
+            }
+            Err(e) => {
+                log::error!(target: "system", "Erro ao ler eventos do logind: {}", e);
+                return;
+            }
+        };
+        log::info!(target: "system", "Evento do sistema: {:?} às {}", event.kind, event.at);
+
+        let action = tracker.on_event(&event);
+        if tracker.suspend_pending() {
+            suspend_pending.set(true);
         }
-    } else {
-        false
-    };
 
-    let conn = init_database().expect("Failed to initialize database");
-    let db_state = DatabaseState {
-        connection: Arc::new(Mutex::new(conn)),
+        if let Some(action) = action {
+            let event_clock = clock.clone();
+            let changed = worker.call(move |conn| {
+                let tx = conn.transaction()?;
+                let task_id = match action {
+                    SuspendAction::Suspend { at } => suspend_running_task(&tx, at)?,
+                    SuspendAction::Resume { task_id, at } => resume_suspended_task(&tx, task_id, at)?.then_some(task_id),
+                };
+                let changed = match task_id {
+                    Some(task_id) => Some((task_id, get_task_remaining_time(&tx, &*event_clock, task_id)?)),
+                    None => None,
+                };
+                tx.commit()?;
+                Ok((action, changed))
+            }).wait();
+
+            match changed {
+                Ok((action, Some((task_id, remaining_seconds)))) => {
+                    if let SuspendAction::Suspend { .. } = action {
+                        tracker.suspended(task_id);
+                    }
+                    let _ = app.emit("tasks-changed", TaskChangedPayload { task_id, remaining_seconds });
+                }
+                Ok((_, None)) => {}
+                Err(e) => log::error!(target: "system", "Erro ao aplicar evento do sistema: {}", e),
+            }
+        }
+
+        // Com a gravação feita: libera a suspensão e, depois da volta, o motor de timer
+        source.event_handled(&event);
+        suspend_pending.set(tracker.suspend_pending());
+    }
+}
+
+// Alt+C global; devolve o gerenciador só se o atalho foi registrado. Roda no setup, depois que
+// o plugin de log já está instalado, para que falhas apareçam no log
+fn register_global_hotkey() -> Option<GlobalHotKeyManager> {
+    log::info!(target: "hotkey", "Criando GlobalHotKeyManager...");
+    let manager = match GlobalHotKeyManager::new() {
+        Ok(manager) => {
+            log::info!(target: "hotkey", "GlobalHotKeyManager criado com sucesso");
+            manager
+        }
+        Err(e) => {
+            log::error!(target: "hotkey", "Erro ao criar GlobalHotKeyManager: {}", e);
+            return None;
+        }
     };
 
+    let hotkey = HotKey::new(Some(Modifiers::ALT), Code::KeyC);
+    log::info!(target: "hotkey", "Registrando atalho Alt+C (ID: {})...", hotkey.id());
+
+    match manager.register(hotkey) {
+        Ok(_) => {
+            log::info!(target: "hotkey", "Atalho global Alt+C registrado com sucesso!");
+            Some(manager)
+        }
+        Err(e) => {
+            log::error!(target: "hotkey", "Erro ao registrar atalho global: {}", e);
+            None
+        }
+    }
+}
+
+// Mantém o gerenciador vivo enquanto o app roda; descartá-lo cancela o atalho
+struct HotkeyState {
+    _manager: GlobalHotKeyManager,
+}
+
+fn main() {
     tauri::Builder::default()
-        .manage(db_state)
         .invoke_handler(tauri::generate_handler![
             toggle_collapse,
             get_collapsed_state,
@@ -1306,22 +616,87 @@ fn main() {
             set_system_volume,
             get_system_mute_status,
             toggle_system_mute,
-            load_tasks,
-            add_task,
-            start_task,
-            pause_task,
-            resume_task,
-            complete_task,
-            delete_task,
-            get_task_remaining_time,
-            get_today_tasks,
+            commands::load_tasks,
+            commands::add_task,
+            commands::update_task,
+            commands::list_pomodoro_profiles,
+            commands::create_pomodoro_profile,
+            commands::delete_pomodoro_profile,
+            commands::set_default_pomodoro_profile,
+            commands::start_task,
+            commands::pause_task,
+            commands::resume_task,
+            commands::complete_task,
+            commands::delete_task,
+            commands::get_task_remaining_time,
+            commands::get_today_tasks,
             expand_window_for_modal,
             reset_window_size,
-            check_pomodoro_sessions,
-            load_tasks_with_sessions,
+            commands::check_pomodoro_sessions,
+            commands::skip_pomodoro_session,
+            commands::extend_pomodoro_session,
+            commands::restart_pomodoro_session,
+            commands::get_offline_policy,
+            commands::set_offline_policy,
+            commands::check_database,
+            commands::load_tasks_with_sessions,
+            commands::get_log_level,
+            commands::set_log_level,
+            commands::list_time_logs,
+            commands::add_time_log,
+            commands::update_time_log,
+            commands::delete_time_log,
+            commands::get_idle_threshold,
+            commands::set_idle_threshold,
+            commands::resolve_idle_time,
+            commands::take_recovery_summary,
+            commands::get_timezone,
+            commands::set_timezone,
+            commands::get_daily_time_totals,
 
         ])
+        .plugin(app_lib::log_plugin())
         .setup(move |app| {
+            log::info!("Iniciando aplicação ClockWise...");
+
+            // Banco no diretório de dados do app (ou no caminho informado por flag/variável de ambiente)
+            let database_path = resolve_database_path(app.path().app_data_dir().ok())?;
+            log::info!(target: "database", "Usando banco de dados em {}", database_path.display());
+            let conn = init_database(&database_path)?;
+
+            // Nível de log escolhido pelo usuário (pode mudar em tempo de execução via set_log_level)
+            log::set_max_level(load_log_level(&conn).unwrap_or(DEFAULT_LOG_LEVEL));
+
+            // Fuso gravado na primeira execução define as fronteiras de dia daqui em diante
+            match ensure_timezone(&conn) {
+                Ok(tz) => log::info!(target: "database", "Fuso horário do usuário: {}", tz.name()),
+                Err(e) => log::error!(target: "database", "Erro ao configurar o fuso horário: {}", e),
+            }
+
+            let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+
+            // Logs abertos fecham no encerramento normal ou, depois de uma queda, no último batimento (antes de repor as sessões)
+            let recovery = recover_stale_time_logs(&conn, &*clock).unwrap_or_else(|e| {
+                log::error!(target: "database", "Erro ao recuperar logs abertos após queda: {}", e);
+                None
+            });
+            app.manage(RecoveryState(Mutex::new(recovery)));
+
+            // Recuperar as transições Pomodoro que deveriam ter acontecido com o app fechado
+            let offline_policy = load_offline_policy(&conn).unwrap_or(OfflinePolicy::Replay);
+            match advance_pomodoro_sessions(&conn, clock.now(), offline_policy) {
+                Ok(tasks) if !tasks.is_empty() => {
+                    log::info!(target: "database", "Sessões recuperadas ({}) para tarefas: {:?}", offline_policy.as_str(), tasks);
+                }
+                Ok(_) => {}
+                Err(e) => log::error!(target: "database", "Erro ao recuperar sessões Pomodoro: {}", e),
+            }
+
+            app.manage(DatabaseState {
+                worker: DbWorker::spawn(conn)?,
+                clock,
+            });
+
             let handle = app.handle();
             let window = handle.get_webview_window("main").unwrap();
 
@@ -1329,6 +704,39 @@ fn main() {
             window.set_size(PhysicalSize::new(1920, 55))?;
             window.set_position(PhysicalPosition::new(0, 0))?;
 
+            // Motor de timer do Pomodoro (fonte de verdade para o frontend)
+            let app_for_timer = handle.clone();
+            let worker_for_timer = handle.state::<DatabaseState>().worker.clone();
+            let clock_for_timer = handle.state::<DatabaseState>().clock.clone();
+            let suspend_pending = SuspendPending::default();
+            let suspend_pending_for_timer = suspend_pending.clone();
+            thread::spawn(move || run_timer_engine(app_for_timer, worker_for_timer, clock_for_timer, suspend_pending_for_timer));
+
+            // Detecção de inatividade (X11 ou logind); sem fonte disponível, fica desligada
+            match detect_idle_source(handle.state::<DatabaseState>().clock.now()) {
+                Some(source) => {
+                    let app_for_idle = handle.clone();
+                    let worker_for_idle = handle.state::<DatabaseState>().worker.clone();
+                    let clock_for_idle = handle.state::<DatabaseState>().clock.clone();
+                    thread::spawn(move || run_idle_monitor(app_for_idle, worker_for_idle, clock_for_idle, IdleMonitor::new(source)));
+                }
+                None => log::warn!(target: "idle",
+                    "Nenhuma fonte de inatividade disponível (extensão MIT-SCREEN-SAVER do X11 ou IdleHint do logind): pausa automática desligada"),
+            }
+
+            // Sinais do logind no barramento do sistema (suspensão, bloqueio de tela, desligamento)
+            let clock_for_events = handle.state::<DatabaseState>().clock.clone();
+            match LogindMonitor::connect(Bus::System, clock_for_events.clone()) {
+                Ok(monitor) => {
+                    let app_for_events = handle.clone();
+                    let worker_for_events = handle.state::<DatabaseState>().worker.clone();
+                    thread::spawn(move || {
+                        run_system_events(app_for_events, worker_for_events, clock_for_events, suspend_pending, Box::new(monitor))
+                    });
+                }
+                Err(e) => log::warn!(target: "system", "Sem eventos do logind (barramento do sistema indisponível): {}", e),
+            }
+
             // Thread para monitorar mudanças de volume do sistema
             let window_for_volume = window.clone();
             thread::spawn(move || {
@@ -1376,47 +784,47 @@ fn main() {
             });
 
             // Se conseguiu registrar o atalho global, configurar o listener
-            if hotkey_registered {
+            if let Some(manager) = register_global_hotkey() {
+                app.manage(HotkeyState { _manager: manager });
                 let window_clone = window.clone();
 
                 thread::spawn(move || {
                     let receiver = GlobalHotKeyEvent::receiver();
-                    println!("🎯 Thread de atalho global iniciada, aguardando eventos...");
-                    println!("   Pressione Alt+C para testar o atalho global");
+                    log::info!(target: "hotkey", "Thread de atalho global iniciada, aguardando Alt+C...");
 
                     loop {
                         match receiver.try_recv() {
                             Ok(event) => {
-                                println!("📨 Evento recebido: ID={}", event.id);
+                                log::debug!(target: "hotkey", "Evento recebido: ID={}", event.id);
 
                                 // Verificar debounce
                                 if !should_process_hotkey() {
                                     continue;
                                 }
 
-                                println!("🎉 Alt+C detectado globalmente!");
+                                log::info!(target: "hotkey", "Alt+C detectado globalmente!");
 
                                 // Alternar estado
                                 let new_state = if let Ok(state) = COLLAPSED_STATE.lock() {
                                     let current = *state;
-                                    println!("🔧 Estado atual: {}, novo estado será: {}", current, !current);
+                                    log::debug!(target: "hotkey", "Estado atual: {}, novo estado será: {}", current, !current);
                                     !current
                                 } else {
-                                    println!("🔧 Erro ao ler estado, usando false");
+                                    log::warn!(target: "hotkey", "Erro ao ler estado, usando false");
                                     false
                                 };
 
                                 // Emitir evento para o frontend
                                 match window_clone.emit("global-hotkey-pressed", new_state) {
-                                    Ok(_) => println!("✓ Evento emitido para o frontend"),
-                                    Err(e) => eprintln!("✗ Erro ao emitir evento: {}", e),
+                                    Ok(_) => log::debug!(target: "hotkey", "Evento emitido para o frontend"),
+                                    Err(e) => log::error!(target: "hotkey", "Erro ao emitir evento: {}", e),
                                 }
 
                                 // Aplicar as mudanças na janela diretamente
                                 let window_for_toggle = window_clone.clone();
                                 tauri::async_runtime::spawn(async move {
                                     if let Err(e) = toggle_collapse(window_for_toggle, new_state).await {
-                                        eprintln!("✗ Erro ao aplicar toggle_collapse: {}", e);
+                                        log::error!(target: "hotkey", "Erro ao aplicar toggle_collapse: {}", e);
                                     }
                                 });
                             }
@@ -1428,7 +836,7 @@ fn main() {
                     }
                 });
             } else {
-                println!("⚠️  Atalho global não foi registrado. Use o botão de teste manual na interface.");
+                log::warn!(target: "hotkey", "Atalho global não foi registrado. Use o botão de teste manual na interface.");
             }
 
             #[cfg(target_os = "linux")]
@@ -1458,193 +866,27 @@ fn main() {
                                 .args(["-i", "-r", window_id, "-e", "0,0,0,1920,55"])
                                 .output();
 
-                            println!("Rust: Configurações wmctrl aplicadas");
+                            log::debug!(target: "window", "Configurações wmctrl aplicadas");
                             break;
                         }
                     }
                 } else {
-                    println!("Rust: wmctrl não encontrado. Por favor, instale com: sudo apt install wmctrl");
+                    log::warn!(target: "window", "wmctrl não encontrado. Por favor, instale com: sudo apt install wmctrl");
                 }
             }
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tauri::test::MockWebviewWindow;
-
-    // Função auxiliar para inicializar schema do banco de dados de teste
-    fn init_database_schema(conn: &Connection) -> SqliteResult<()> {
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS tasks (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                user TEXT NOT NULL,
-                estimated_hours REAL NOT NULL,
-                scheduled_date TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'pending',
-                created_at TEXT NOT NULL,
-                started_at TEXT,
-                completed_at TEXT
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS task_time_logs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                task_id INTEGER NOT NULL,
-                started_at TEXT NOT NULL,
-                ended_at TEXT NULL,
-                FOREIGN KEY (task_id) REFERENCES tasks (id) ON DELETE CASCADE
-            )",
-            [],
-        )?;
-
-        Ok(())
-    }
-
-    // Teste para expand_window_for_modal
-    #[tokio::test]
-    async fn test_expand_window_for_modal() {
-        let mock_window = MockWebviewWindow::new();
-
-        // Teste expandir janela
-        let result = expand_window_for_modal(mock_window.clone()).await;
-        assert!(result.is_ok(), "Deveria expandir a janela com sucesso");
-
-        // Verificar se o tamanho foi definido corretamente
-        let size = mock_window.size().unwrap();
-        assert_eq!(size.width, 1920);
-        assert_eq!(size.height, 500);
-
-        // Verificar posição
-        let position = mock_window.position().unwrap();
-        assert_eq!(position.x, 0);
-        assert_eq!(position.y, 0);
-    }
-
-    // Teste para reset_window_size
-    #[tokio::test]
-    async fn test_reset_window_size() {
-        let mock_window = MockWebviewWindow::new();
-
-        // Teste resetar tamanho
-        let result = reset_window_size(mock_window.clone()).await;
-        assert!(result.is_ok(), "Deveria resetar a janela com sucesso");
-
-        // Verificar se o tamanho foi resetado corretamente
-        let size = mock_window.size().unwrap();
-        assert_eq!(size.width, 1920);
-        assert_eq!(size.height, 55);
-
-        // Verificar posição
-        let position = mock_window.position().unwrap();
-        assert_eq!(position.x, 0);
-        assert_eq!(position.y, 0);
-    }
-
-    // Testes para as funções de tarefa
-    #[tokio::test]
-    async fn test_task_lifecycle() {
-        // Criar conexão de teste
-        let conn = Connection::open_in_memory().unwrap();
-        init_database_schema(&conn).unwrap();
-
-        let db_state = DatabaseState {
-            connection: Arc::new(Mutex::new(conn)),
-        };
-
-        let task = add_task(
-            "Teste".to_string(),
-            "Usuário".to_string(),
-            2.0,
-            "2024-03-14".to_string(),
-            State::new(db_state.clone())
-        ).await;
-        assert!(task.is_ok(), "Deveria criar tarefa com sucesso");
-        let task = task.unwrap();
-
-        // Teste iniciar tarefa
-        let start_result = start_task(
-            task.id.unwrap(),
-            State::new(db_state.clone())
-        ).await;
-        assert!(start_result.is_ok(), "Deveria iniciar tarefa com sucesso");
-
-        // Verificar se apenas uma tarefa pode estar ativa
-        let another_task = add_task(
-            "Outra".to_string(),
-            "Usuário".to_string(),
-            1.0,
-            "2024-03-14".to_string(),
-            State::new(db_state.clone())
-        ).await.unwrap();
-
-        let start_another = start_task(
-            another_task.id.unwrap(),
-            State::new(db_state.clone())
-        ).await;
-        assert!(start_another.is_err(), "Não deveria permitir iniciar outra tarefa");
-
-        // Teste completar tarefa
-        let complete_result = complete_task(
-            task.id.unwrap(),
-            State::new(db_state.clone())
-        ).await;
-        assert!(complete_result.is_ok(), "Deveria completar tarefa com sucesso");
-
-        // Teste deletar tarefa
-        let delete_result = delete_task(
-            task.id.unwrap(),
-            State::new(db_state.clone())
-        ).await;
-        assert!(delete_result.is_ok(), "Deveria deletar tarefa com sucesso");
-    }
-
-    // Teste para get_today_tasks
-    #[tokio::test]
-    async fn test_get_today_tasks() {
-        // Criar conexão de teste
-        let conn = Connection::open_in_memory().unwrap();
-        init_database_schema(&conn).unwrap();
-
-        let db_state = DatabaseState {
-            connection: Arc::new(Mutex::new(conn)),
-        };
-
-        // Adicionar algumas tarefas
-        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-        let yesterday = (chrono::Local::now() - chrono::Duration::days(1))
-            .format("%Y-%m-%d").to_string();
-
-        // Tarefa de hoje
-        add_task(
-            "Hoje".to_string(),
-            "Usuário".to_string(),
-            1.0,
-            today.clone(),
-            State::new(db_state.clone())
-        ).await.unwrap();
-
-        // Tarefa de ontem
-        add_task(
-            "Ontem".to_string(),
-            "Usuário".to_string(),
-            1.0,
-            yesterday,
-            State::new(db_state.clone())
-        ).await.unwrap();
-
-        // Buscar tarefas de hoje
-        let today_tasks = get_today_tasks(State::new(db_state.clone())).await.unwrap();
-
-        assert_eq!(today_tasks.len(), 1, "Deveria retornar apenas uma tarefa de hoje");
-        assert_eq!(today_tasks[0].scheduled_date, today);
-    }
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // Saída normal: na próxima inicialização os logs abertos fecham neste horário
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<DatabaseState>();
+                let clock = state.clock.clone();
+                if let Err(e) = state.worker.call(move |conn| Ok(mark_clean_shutdown(conn, &*clock)?)).wait() {
+                    log::error!(target: "database", "Erro ao registrar encerramento normal: {}", e);
+                }
+            }
+        });
 }
@@ -3,19 +3,23 @@ import { useTaskStore } from "./store/task.store"
 import { TaskFooter } from "./componnets/TaskFooter"
 import { TaskModal } from "./componnets/TaskModal"
 import { usePomodoroChecker } from "./hooks/usePomodoroChecker"
+import { useIdleReturnPrompt } from "./hooks/useIdleReturnPrompt"
+import { useRecoverySummary } from "./hooks/useRecoverySummary"
 import { invoke } from "@tauri-apps/api/core"
 
 function App() {
-  const { loadTasks, loadTasksWithSessions } = useTaskStore()
+  const { loadTasks, loadTasksWithSessions, loadTimezone } = useTaskStore()
   const [isModalOpen, setIsModalOpen] = useState(false)
   const buttonRef = useRef<HTMLButtonElement>(null)
 
   // Verificador automático de sessões Pomodoro
-  usePomodoroChecker(5000) // Verifica a cada 5 segundos
+  usePomodoroChecker() // Rede de segurança; o backend emite "session-transition"
+  useIdleReturnPrompt()
+  useRecoverySummary()
 
   useEffect(() => {
     const init = async () => {
-      await Promise.all([loadTasks(), loadTasksWithSessions()])
+      await Promise.all([loadTimezone(), loadTasks(), loadTasksWithSessions()])
     }
     init()
   }, [])
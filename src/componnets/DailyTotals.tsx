@@ -0,0 +1,63 @@
+import { useEffect, useState } from "react"
+import { useTaskStore } from "../store/task.store"
+import { todayInTimezone } from "../utils/format"
+
+const DAYS = 7
+
+// AAAA-MM-DD deslocado em dias, sem passar pelo fuso do navegador
+function shiftDate(date: string, days: number): string {
+  const [year, month, day] = date.split("-").map(Number)
+  return new Date(Date.UTC(year, month - 1, day + days)).toISOString().slice(0, 10)
+}
+
+function formatHours(seconds: number): string {
+  const hours = Math.floor(seconds / 3600)
+  const minutes = Math.floor((seconds % 3600) / 60)
+  return `${hours}h${String(minutes).padStart(2, "0")}`
+}
+
+// Tempo trabalhado por dia nos últimos 7 dias, com as fronteiras de dia no fuso do usuário
+export function DailyTotals() {
+  const { getDailyTimeTotals, timezone, tasks } = useTaskStore()
+  const [totals, setTotals] = useState<Record<string, number>>({})
+
+  const to = todayInTimezone(timezone)
+  const from = shiftDate(to, -(DAYS - 1))
+
+  useEffect(() => {
+    const load = async () => {
+      const daily = await getDailyTimeTotals(from, to)
+      const byDate: Record<string, number> = {}
+      daily.forEach(total => {
+        byDate[total.date] = (byDate[total.date] ?? 0) + total.seconds
+      })
+      setTotals(byDate)
+    }
+    load()
+  }, [getDailyTimeTotals, from, to, tasks])
+
+  const days = Array.from({ length: DAYS }, (_, index) => shiftDate(from, index))
+
+  return (
+    <div className="flex items-center gap-2 px-4 py-3 border-b border-zinc-700 overflow-x-auto scrollbar-hide">
+      <span className="text-white/70 text-sm uppercase mr-2">Últimos {DAYS} dias</span>
+      {days.map(date => {
+        const [, month, day] = date.split("-")
+        const seconds = totals[date] ?? 0
+        return (
+          <div
+            key={date}
+            className={`flex flex-col items-center min-w-16 px-3 py-1 rounded-full ${
+              date === to ? "bg-[#17FF8B]/20 text-[#17FF8B]" : "bg-zinc-800 text-gray-300"
+            }`}
+          >
+            <span className="text-xs text-gray-400">
+              {day}/{month}
+            </span>
+            <span className="text-sm font-mono">{seconds > 0 ? formatHours(seconds) : "—"}</span>
+          </div>
+        )
+      })}
+    </div>
+  )
+}
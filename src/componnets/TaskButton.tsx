@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core"
+import { listen } from "@tauri-apps/api/event"
 import { useEffect, useState, useRef } from "react"
-import type { Task, TaskWithActiveSession } from "../store/task.store"
+import type { Task, TaskWithActiveSession, TimerTick } from "../store/task.store"
 import { formatTimeDisplay } from "../utils/format"
 import { calculateTimeRemaining, secondsToDuration } from "../utils/time"
 import { SettingsIcon } from "../components/SettingsIcon"
@@ -110,55 +111,21 @@ export function TaskButton({ task, onDragAction }: TaskButtonProps) {
     }
   }, [isSwapped, wasActivated, task.id, task.name])
 
-  // Timer inteligente - Pomodoro ou tempo total
+  // Tempo restante vem do motor de timer do backend (evento "timer-tick" a cada segundo)
   useEffect(() => {
-    let timer: NodeJS.Timeout | null = null
+    if (!task.id) return
 
-    // Só roda timer se tarefa está ativa e não pausada
-    const shouldRunTimer = (task.status === "in_progress" || task.status === "waiting") && !isPaused
-
-    if (shouldRunTimer) {
-      // SEMPRE usar o tempo real da tarefa, não do Pomodoro
-      // O Pomodoro controla os ciclos, mas o timer deve mostrar o tempo restante da tarefa
-      // Permitir timer rodar mesmo quando negativo
-      const shouldStartTimer = true // Timer sempre roda quando tarefa está ativa
-
-      if (shouldStartTimer) {
-        timer = setInterval(() => {
-          setCurrentTimeRemaining(prev => {
-            // Calcular total de segundos atuais (considerando se é negativo)
-            let totalSeconds = prev.hours * 3600 + prev.minutes * 60 + prev.seconds
-
-            if (prev.isNegative) {
-              totalSeconds = -totalSeconds
-            }
-
-            // Decrementar 1 segundo
-            totalSeconds -= 1
-
-            // Determinar se é negativo
-            const isNegative = totalSeconds < 0
-            const absSeconds = Math.abs(totalSeconds)
-
-            // Converter de volta para horas, minutos, segundos
-            const hours = Math.floor(absSeconds / 3600)
-            const minutes = Math.floor((absSeconds % 3600) / 60)
-            const seconds = Math.floor(absSeconds % 60)
-
-            // Timer funcionando corretamente
-
-            return { hours, minutes, seconds, isNegative }
-          })
-        }, 1000)
+    const unlisten = listen<TimerTick[]>("timer-tick", event => {
+      const tick = event.payload.find(timer => String(timer.task_id) === String(task.id))
+      if (tick) {
+        setCurrentTimeRemaining(secondsToDuration(tick.task_remaining_seconds))
       }
-    }
+    })
 
     return () => {
-      if (timer) {
-        clearInterval(timer)
-      }
+      unlisten.then(fn => fn())
     }
-  }, [task.status, isPaused, task.id])
+  }, [task.id])
 
   // Atualizar tempo quando tarefa muda ou quando não está ativa
   useEffect(() => {
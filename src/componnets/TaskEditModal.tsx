@@ -3,9 +3,12 @@ import { useState, useEffect } from "react"
 import { useTaskStore } from "../store/task.store"
 import type { Task } from "../store/task.store"
 import { invoke } from "@tauri-apps/api/core"
-import { X, Trash2, Play, Pause, Square } from "lucide-react"
+import { X, Trash2, Play, Pause, Square, SkipForward, Plus, RotateCcw } from "lucide-react"
 import TimeInput from "./TimeInput"
 import DateInput from "./DateInput"
+import { TimeLogEditor } from "./TimeLogEditor"
+import { errorMessage } from "../utils/errors"
+import { formatCalendarDate } from "../utils/format"
 
 interface TaskEditModalProps {
   isOpen: boolean
@@ -15,7 +18,15 @@ interface TaskEditModalProps {
 }
 
 export function TaskEditModal({ isOpen, onClose, anchorEl, task }: TaskEditModalProps) {
-  const { deleteTask, completeTask, pauseTask } = useTaskStore()
+  const {
+    deleteTask,
+    completeTask,
+    pauseTask,
+    updateTask,
+    skipPomodoroSession,
+    extendPomodoroSession,
+    restartPomodoroSession
+  } = useTaskStore()
   const [taskName, setTaskName] = useState(task.name)
   const [timeInput, setTimeInput] = useState(() => {
     const hours = Math.floor(task.estimated_hours || 0)
@@ -100,16 +111,13 @@ export function TaskEditModal({ isOpen, onClose, anchorEl, task }: TaskEditModal
         parseInt(timeInput.split(":")[1]) / 60 +
         parseInt(timeInput.split(":")[2]) / 3600
 
-      const updatedTask: Task = {
-        ...task,
-        name: taskName.trim(),
-        estimated_hours: totalHours,
-        scheduled_date: scheduledDate.toISOString().split("T")[0]
+      if (task.id) {
+        await updateTask(task.id, {
+          name: taskName.trim(),
+          estimated_hours: totalHours,
+          scheduled_date: formatCalendarDate(scheduledDate)
+        })
       }
-
-      // Por enquanto, apenas fechamos o modal
-      // A funcionalidade de updateTask pode ser implementada futuramente
-      console.log("Task atualizada:", updatedTask)
       onClose()
 
       setTimeout(async () => {
@@ -117,11 +125,7 @@ export function TaskEditModal({ isOpen, onClose, anchorEl, task }: TaskEditModal
       }, 100)
     } catch (error: unknown) {
       console.error("Erro ao atualizar tarefa:", error)
-      if (error instanceof Error) {
-        alert(`Erro ao atualizar tarefa: ${error.message}`)
-      } else {
-        alert(`Erro ao atualizar tarefa: ${String(error)}`)
-      }
+      alert(`Erro ao atualizar tarefa: ${errorMessage(error)}`)
     } finally {
       setIsLoading(false)
     }
@@ -180,6 +184,22 @@ export function TaskEditModal({ isOpen, onClose, anchorEl, task }: TaskEditModal
     }
   }
 
+  // Controles da sessão Pomodoro em andamento (trabalho ou pausa)
+  const handleSessionAction = async (action: (taskId: string) => Promise<void>) => {
+    if (isLoading || !task.id) return
+    setIsLoading(true)
+    try {
+      await action(task.id)
+      onClose()
+    } catch (error) {
+      console.error("Erro ao alterar a sessão Pomodoro:", error)
+    } finally {
+      setIsLoading(false)
+    }
+  }
+
+  const isSessionRunning = task.status === "in_progress" || task.status === "waiting"
+
   const getTaskStatusColor = () => {
     switch (task.status) {
       case "in_progress":
@@ -325,6 +345,51 @@ export function TaskEditModal({ isOpen, onClose, anchorEl, task }: TaskEditModal
           </button>
         </div>
 
+        {/* Pomodoro Session Controls */}
+        {isSessionRunning && (
+          <div className="flex gap-2" onClick={e => e.stopPropagation()}>
+            <button
+              type="button"
+              onClick={e => {
+                e.stopPropagation()
+                handleSessionAction(skipPomodoroSession)
+              }}
+              disabled={isLoading}
+              className="flex-1 h-8 flex items-center justify-center gap-2 cursor-pointer bg-zinc-700/50 hover:bg-zinc-700 text-white rounded-full transition-colors disabled:opacity-50"
+            >
+              <SkipForward className="w-3 h-3" />
+              Pular
+            </button>
+            <button
+              type="button"
+              onClick={e => {
+                e.stopPropagation()
+                handleSessionAction(taskId => extendPomodoroSession(taskId, 5 * 60))
+              }}
+              disabled={isLoading}
+              className="flex-1 h-8 flex items-center justify-center gap-2 cursor-pointer bg-zinc-700/50 hover:bg-zinc-700 text-white rounded-full transition-colors disabled:opacity-50"
+            >
+              <Plus className="w-3 h-3" />
+              5 min
+            </button>
+            <button
+              type="button"
+              onClick={e => {
+                e.stopPropagation()
+                handleSessionAction(restartPomodoroSession)
+              }}
+              disabled={isLoading}
+              className="flex-1 h-8 flex items-center justify-center gap-2 cursor-pointer bg-zinc-700/50 hover:bg-zinc-700 text-white rounded-full transition-colors disabled:opacity-50"
+            >
+              <RotateCcw className="w-3 h-3" />
+              Reiniciar
+            </button>
+          </div>
+        )}
+
+        {/* Time Logs */}
+        {task.id && <TimeLogEditor taskId={task.id} />}
+
         {/* Action Buttons */}
         <div className="flex justify-between gap-4 mt-2" onClick={e => e.stopPropagation()}>
           <button
@@ -1,13 +1,14 @@
 import { useTaskStore } from "../store/task.store"
 import { useState, useEffect, useMemo } from "react"
 import type { RefObject } from "react"
-import { Calendar as CalendarIcon, Clock, Plus, List } from "lucide-react"
+import { Calendar as CalendarIcon, Clock, Plus, List, Moon } from "lucide-react"
 // import { VolumeSlider } from "./VolumeSlider"
 import { TaskButton } from "./TaskButton"
 import Calendar from "./Calendar"
 import { TaskList } from "./TaskList"
 import { TaskListView } from "./TaskListView"
 import { invoke } from "@tauri-apps/api/core"
+import { formatCalendarDate } from "../utils/format"
 
 interface TaskFooterProps {
   onAddClick: () => void
@@ -21,7 +22,8 @@ export function TaskFooter({ onAddClick, buttonRef }: TaskFooterProps) {
     tasks,
     tasksWithSessions,
     loadTasks,
-    loadTasksWithSessions
+    loadTasksWithSessions,
+    idlePausedPeriod
   } = useTaskStore()
   const [currentTime, setCurrentTime] = useState(new Date())
   const [isCalendarOpen, setIsCalendarOpen] = useState(false)
@@ -56,7 +58,7 @@ export function TaskFooter({ onAddClick, buttonRef }: TaskFooterProps) {
   // Pegar tarefas do dia selecionado
   const getTasksForDate = useMemo(() => {
     return (date: Date) => {
-      const dateStr = date.toISOString().split("T")[0]
+      const dateStr = formatCalendarDate(date)
       return allTasks.filter(task => task.scheduled_date === dateStr)
     }
   }, [allTasks])
@@ -74,7 +76,7 @@ export function TaskFooter({ onAddClick, buttonRef }: TaskFooterProps) {
 
   const handleDaySelect = (date: Date | undefined) => {
     if (date) {
-      const dateStr = date.toISOString().split("T")[0]
+      const dateStr = formatCalendarDate(date)
       const tasksForDate = allTasks.filter(task => task.scheduled_date === dateStr)
 
       if (tasksForDate.length > 0) {
@@ -160,6 +162,23 @@ export function TaskFooter({ onAddClick, buttonRef }: TaskFooterProps) {
 
         {/* System Section with Add Button */}
         <div className="flex items-center gap-4 flex-shrink-0">
+          {/* Pausa automática por inatividade, até o usuário voltar e responder */}
+          {idlePausedPeriod && (
+            <div
+              className="flex items-center gap-1 text-xs text-[#FF396D]"
+              title="Tarefa pausada por inatividade"
+            >
+              <Moon className="w-4 h-4" />
+              <span>
+                Inativo desde{" "}
+                {new Date(idlePausedPeriod.started_at).toLocaleTimeString("pt-BR", {
+                  hour: "2-digit",
+                  minute: "2-digit"
+                })}
+              </span>
+            </div>
+          )}
+
           {/* Task List View Button */}
           <button
             onClick={async () => {
@@ -2,6 +2,7 @@ import { useState, useEffect } from "react"
 import { useTaskStore, type Task } from "../store/task.store"
 import { TaskFilter } from "./TaskFilter"
 import { TaskCard } from "./TaskCard"
+import { DailyTotals } from "./DailyTotals"
 import { Filter, X, Search } from "lucide-react"
 
 interface TaskListViewProps {
@@ -86,6 +87,9 @@ export function TaskListView({ isOpen, onClose }: TaskListViewProps) {
 
           {/* Conteúdo principal */}
           <div className="flex-1 flex flex-col">
+            {/* Tempo trabalhado por dia */}
+            <DailyTotals />
+
             {/* Barra de busca */}
             <div className="p-4 border-b border-zinc-700">
               <div className="relative">
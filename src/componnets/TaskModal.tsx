@@ -6,6 +6,8 @@ import { invoke } from "@tauri-apps/api/core"
 import { Check, X } from "lucide-react"
 import TimeInput from "./TimeInput"
 import DateInput from "./DateInput"
+import { errorMessage } from "../utils/errors"
+import { formatCalendarDate } from "../utils/format"
 
 interface TaskModalProps {
   isOpen: boolean
@@ -52,7 +54,7 @@ export function TaskModal({ isOpen, onClose, anchorEl }: TaskModalProps) {
         name: taskName.trim(),
         user: "Gabriel",
         estimated_hours: totalHours,
-        scheduled_date: scheduledDate.toISOString().split("T")[0],
+        scheduled_date: formatCalendarDate(scheduledDate),
         status: "pending" as const,
         created_at: new Date().toISOString(),
         started_at: null,
@@ -70,11 +72,7 @@ export function TaskModal({ isOpen, onClose, anchorEl }: TaskModalProps) {
       }, 100)
     } catch (error: unknown) {
       console.error("Erro detalhado ao adicionar tarefa:", error)
-      if (error instanceof Error) {
-        alert(`Erro ao adicionar tarefa: ${error.message}`)
-      } else {
-        alert(`Erro ao adicionar tarefa: ${String(error)}`)
-      }
+      alert(`Erro ao adicionar tarefa: ${errorMessage(error)}`)
     }
   }
 
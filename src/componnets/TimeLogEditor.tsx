@@ -0,0 +1,179 @@
+import { useCallback, useEffect, useState } from "react"
+import { Check, Plus, Trash2 } from "lucide-react"
+import { useTaskStore } from "../store/task.store"
+import type { TaskTimeLog } from "../store/task.store"
+import { errorMessage } from "../utils/errors"
+import { fromDateTimeLocal, toDateTimeLocal } from "../utils/format"
+
+interface TimeLogEditorProps {
+  taskId: string
+}
+
+type LogDraft = {
+  startedAt: string
+  endedAt: string
+}
+
+const emptyDraft: LogDraft = { startedAt: "", endedAt: "" }
+
+const inputClass =
+  "flex-1 min-w-0 h-7 px-3 bg-zinc-700/50 rounded-full text-white text-xs font-mono focus:outline-none"
+
+// Correção manual dos registros de tempo; validação e sobreposição (inclusive com outras tarefas) ficam no backend
+export function TimeLogEditor({ taskId }: TimeLogEditorProps) {
+  const { listTimeLogs, addTimeLog, updateTimeLog, deleteTimeLog } = useTaskStore()
+  const [logs, setLogs] = useState<TaskTimeLog[]>([])
+  const [drafts, setDrafts] = useState<Record<number, LogDraft>>({})
+  const [newLog, setNewLog] = useState<LogDraft>(emptyDraft)
+  const [error, setError] = useState<string | null>(null)
+  const [isSaving, setIsSaving] = useState(false)
+
+  const reload = useCallback(async () => {
+    const loaded = await listTimeLogs(taskId)
+    setLogs(loaded)
+    setDrafts(
+      Object.fromEntries(
+        loaded
+          .filter(log => log.id !== undefined && log.ended_at)
+          .map(log => [
+            log.id,
+            { startedAt: toDateTimeLocal(log.started_at), endedAt: toDateTimeLocal(log.ended_at!) }
+          ])
+      )
+    )
+  }, [listTimeLogs, taskId])
+
+  useEffect(() => {
+    reload()
+  }, [reload])
+
+  const run = async (action: () => Promise<void>) => {
+    if (isSaving) return
+    setIsSaving(true)
+    setError(null)
+    try {
+      await action()
+      await reload()
+    } catch (error) {
+      setError(errorMessage(error))
+    } finally {
+      setIsSaving(false)
+    }
+  }
+
+  const setDraft = (logId: number, changes: Partial<LogDraft>) => {
+    setDrafts(current => ({ ...current, [logId]: { ...current[logId], ...changes } }))
+  }
+
+  const handleSave = (logId: number) =>
+    run(() => {
+      const draft = drafts[logId]
+      return updateTimeLog(logId, fromDateTimeLocal(draft.startedAt), fromDateTimeLocal(draft.endedAt))
+    })
+
+  const handleDelete = (logId: number) => {
+    if (!confirm("Excluir este registro de tempo?")) return
+    run(() => deleteTimeLog(logId))
+  }
+
+  const handleAdd = () => {
+    if (!newLog.startedAt || !newLog.endedAt) {
+      setError("Informe início e fim do registro")
+      return
+    }
+    run(async () => {
+      await addTimeLog(taskId, fromDateTimeLocal(newLog.startedAt), fromDateTimeLocal(newLog.endedAt))
+      setNewLog(emptyDraft)
+    })
+  }
+
+  return (
+    <div className="flex flex-col gap-2" onClick={e => e.stopPropagation()}>
+      <label className="text-white/70 text-sm uppercase px-6 py-1">Registros de tempo</label>
+
+      <div className="flex flex-col gap-2 max-h-40 overflow-y-auto px-2">
+        {logs.length === 0 && <span className="text-zinc-400 text-xs text-center">Nenhum registro ainda</span>}
+
+        {logs.map(log => {
+          const draft = log.id !== undefined ? drafts[log.id] : undefined
+
+          // Log aberto pertence à sessão em andamento: só pausando a tarefa ele pode ser corrigido
+          if (!log.ended_at || !draft || log.id === undefined) {
+            return (
+              <div key={log.id} className="flex items-center justify-center gap-2 text-xs text-[#17FF8B]">
+                {new Date(log.started_at).toLocaleString()} → em andamento
+              </div>
+            )
+          }
+
+          const logId = log.id
+          return (
+            <div key={logId} className="flex items-center gap-1">
+              <input
+                type="datetime-local"
+                step={1}
+                value={draft.startedAt}
+                onChange={e => setDraft(logId, { startedAt: e.target.value })}
+                className={inputClass}
+                disabled={isSaving}
+              />
+              <input
+                type="datetime-local"
+                step={1}
+                value={draft.endedAt}
+                onChange={e => setDraft(logId, { endedAt: e.target.value })}
+                className={inputClass}
+                disabled={isSaving}
+              />
+              <button
+                type="button"
+                onClick={() => handleSave(logId)}
+                disabled={isSaving}
+                className="h-7 w-7 shrink-0 flex items-center justify-center bg-[#17FF8B]/80 hover:bg-[#17FF8B] text-black rounded-full transition-colors disabled:opacity-50"
+              >
+                <Check className="w-3 h-3" />
+              </button>
+              <button
+                type="button"
+                onClick={() => handleDelete(logId)}
+                disabled={isSaving}
+                className="h-7 w-7 shrink-0 flex items-center justify-center bg-red-600/80 hover:bg-red-600 text-white rounded-full transition-colors disabled:opacity-50"
+              >
+                <Trash2 className="w-3 h-3" />
+              </button>
+            </div>
+          )
+        })}
+
+        <div className="flex items-center gap-1">
+          <input
+            type="datetime-local"
+            step={1}
+            value={newLog.startedAt}
+            onChange={e => setNewLog(current => ({ ...current, startedAt: e.target.value }))}
+            className={inputClass}
+            disabled={isSaving}
+          />
+          <input
+            type="datetime-local"
+            step={1}
+            value={newLog.endedAt}
+            onChange={e => setNewLog(current => ({ ...current, endedAt: e.target.value }))}
+            className={inputClass}
+            disabled={isSaving}
+          />
+          <button
+            type="button"
+            onClick={handleAdd}
+            disabled={isSaving}
+            className="h-7 w-7 shrink-0 flex items-center justify-center bg-blue-600/80 hover:bg-blue-600 text-white rounded-full transition-colors disabled:opacity-50"
+          >
+            <Plus className="w-3 h-3" />
+          </button>
+        </div>
+      </div>
+
+      {error && <span className="text-[#FF396D] text-xs text-center px-6">{error}</span>}
+    </div>
+  )
+}
@@ -0,0 +1,34 @@
+import { useEffect } from "react"
+import { listen } from "@tauri-apps/api/event"
+import { useTaskStore } from "../store/task.store"
+import type { IdlePeriod } from "../store/task.store"
+
+// Quando o backend pausa a tarefa por inatividade, marca a pausa no rodapé e pergunta na volta
+// se o tempo parado conta. O período fica guardado no backend; aqui só vai a resposta
+export function useIdleReturnPrompt() {
+  const { resolveIdleTime, setIdlePausedPeriod } = useTaskStore()
+
+  useEffect(() => {
+    const unlistenPaused = listen<IdlePeriod>("idle-paused", event => {
+      setIdlePausedPeriod(event.payload)
+    })
+
+    const unlistenReturned = listen<IdlePeriod>("idle-returned", async event => {
+      const period = event.payload
+      if (!period.ended_at) return
+
+      const minutes = Math.round(
+        (new Date(period.ended_at).getTime() - new Date(period.started_at).getTime()) / 60000
+      )
+      const keep = window.confirm(
+        `A tarefa foi pausada por inatividade (${minutes} min). Contar esse tempo como trabalhado?`
+      )
+      await resolveIdleTime(keep)
+    })
+
+    return () => {
+      unlistenPaused.then(fn => fn())
+      unlistenReturned.then(fn => fn())
+    }
+  }, [resolveIdleTime, setIdlePausedPeriod])
+}
@@ -1,7 +1,10 @@
 import { useEffect, useRef } from "react"
+import { listen } from "@tauri-apps/api/event"
 import { useTaskStore } from "../store/task.store"
 
-export function usePomodoroChecker(intervalMs: number = 5000) {
+// O backend avança as sessões sozinho (evento "session-transition"); a verificação
+// periódica fica apenas como rede de segurança
+export function usePomodoroChecker(intervalMs: number = 30000) {
   const { checkPomodoroSessions, loadTasks, loadTasksWithSessions } = useTaskStore()
   const intervalRef = useRef<NodeJS.Timeout | null>(null)
 
@@ -28,6 +31,19 @@ export function usePomodoroChecker(intervalMs: number = 5000) {
     }
   }, [checkPomodoroSessions, loadTasks, loadTasksWithSessions, intervalMs])
 
+  // Recarregar quando o backend avisar que alguma tarefa mudou ou avançou de sessão
+  useEffect(() => {
+    const unlisteners = ["tasks-changed", "session-transition"].map(event =>
+      listen(event, async () => {
+        await Promise.all([loadTasks(), loadTasksWithSessions()])
+      })
+    )
+
+    return () => {
+      unlisteners.forEach(unlisten => unlisten.then(fn => fn()))
+    }
+  }, [loadTasks, loadTasksWithSessions])
+
   // Função para verificação manual
   const checkNow = async () => {
     try {
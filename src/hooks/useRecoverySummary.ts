@@ -0,0 +1,25 @@
+import { useEffect } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import type { RecoverySummary } from "../store/task.store"
+
+// Avisa uma vez, ao abrir, quais logs ficaram abertos por uma queda do app e onde foram fechados
+export function useRecoverySummary() {
+  useEffect(() => {
+    const check = async () => {
+      try {
+        const summary = await invoke<RecoverySummary | null>("take_recovery_summary")
+        if (!summary || summary.logs.length === 0) return
+
+        const lines = summary.logs.map(log => {
+          const closedAt = new Date(log.closed_at).toLocaleTimeString()
+          const discardedMinutes = Math.round(log.discarded_seconds / 60)
+          return `• ${log.task_name}: parada às ${closedAt} (${discardedMinutes} min não contados)`
+        })
+        window.alert(`O ClockWise foi encerrado inesperadamente. Tarefas pausadas:\n${lines.join("\n")}`)
+      } catch (error) {
+        console.error("Erro ao buscar resumo de recuperação:", error)
+      }
+    }
+    check()
+  }, [])
+}
@@ -1,5 +1,11 @@
 import { create } from "zustand"
 import { invoke } from "@tauri-apps/api/core"
+import { isAppError } from "../utils/errors"
+import { todayInTimezone } from "../utils/format"
+
+// Mesmos valores serializados pelos enums TaskStatus e SessionType do backend
+export type TaskStatus = "pending" | "in_progress" | "paused" | "waiting" | "completed"
+export type SessionType = "work" | "break"
 
 export type Task = {
   id?: string
@@ -7,27 +13,54 @@ export type Task = {
   user: string
   estimated_hours: number
   scheduled_date: string
-  status: "pending" | "in_progress" | "paused" | "waiting" | "completed"
+  status: TaskStatus
   created_at: string
   started_at: string | null
   completed_at: string | null
+  profile_id?: number | null
+}
+
+export type PomodoroProfile = {
+  id?: number
+  name: string
+  work_seconds: number
+  short_break_seconds: number
+  long_break_seconds: number
+  rounds_before_long_break: number
+  total_rounds: number
+  is_default: boolean
 }
 
 export type ActiveSessionInfo = {
-  session_type: "work" | "break"
+  session_type: SessionType
   started_at: string
   ends_at: string
   duration_seconds: number
+  elapsed_seconds: number
+}
+
+// Payload do evento "timer-tick", emitido pelo backend a cada segundo para cada sessão ativa
+export type TimerTick = {
+  task_id: number
+  pomodoro_id: number
+  session_number: number
+  session_type: SessionType
+  remaining_seconds: number
+  ends_at: string
+  task_remaining_seconds: number
 }
 
 export type PomodoroSessionInfo = {
   id?: number
   session_number: number
-  session_type: "work" | "break"
+  session_type: SessionType
   duration_seconds: number
   created_at: string
   is_active: boolean
+  status: "pending" | "running" | "completed" | "skipped" | "interrupted"
   started_at?: string | null
+  ended_at?: string | null
+  elapsed_seconds: number
 }
 
 export type TaskWithActiveSession = Task & {
@@ -35,12 +68,56 @@ export type TaskWithActiveSession = Task & {
   pomodoro_sessions: PomodoroSessionInfo[]
 }
 
+export type TaskTimeLog = {
+  id?: number
+  task_id: number
+  started_at: string
+  ended_at: string | null
+}
+
+export type IdlePeriod = {
+  task_id: number
+  started_at: string
+  ended_at: string | null
+}
+
+export type RecoveredTimeLog = {
+  log_id: number
+  task_id: number
+  task_name: string
+  started_at: string
+  closed_at: string
+  discarded_seconds: number
+}
+
+export type RecoverySummary = {
+  recovered_at: string
+  logs: RecoveredTimeLog[]
+}
+
+// Tempo de uma tarefa num dia (AAAA-MM-DD) do fuso do usuário
+export type DailyTimeTotal = {
+  date: string
+  task_id: number
+  seconds: number
+}
+
+export type TaskUpdate = Partial<Pick<Task, "name" | "user" | "estimated_hours" | "scheduled_date">>
+
 type TaskStore = {
   tasks: Task[]
   tasksWithSessions: TaskWithActiveSession[]
+  timezone: string | null
+  // Tarefa pausada pelo monitor de inatividade, até o usuário responder se o tempo conta
+  idlePausedPeriod: IdlePeriod | null
+  setIdlePausedPeriod: (period: IdlePeriod | null) => void
+  loadTimezone: () => Promise<void>
+  setTimezone: (timezone: string) => Promise<void>
+  getDailyTimeTotals: (from: string, to: string) => Promise<DailyTimeTotal[]>
   loadTasks: () => Promise<void>
   loadTasksWithSessions: () => Promise<void>
   addTask: (task: Task) => Promise<void>
+  updateTask: (taskId: string, changes: TaskUpdate) => Promise<void>
   startTask: (taskId: string) => Promise<void>
   startTaskWithoutStopping: (taskId: string) => Promise<void>
   pauseTask: (taskId: string) => Promise<void>
@@ -49,6 +126,14 @@ type TaskStore = {
   deleteTask: (taskId: string) => Promise<void>
   getTaskRemainingTime: (taskId: string) => Promise<number>
   checkPomodoroSessions: () => Promise<number[]>
+  skipPomodoroSession: (taskId: string) => Promise<void>
+  extendPomodoroSession: (taskId: string, seconds: number) => Promise<void>
+  restartPomodoroSession: (taskId: string) => Promise<void>
+  listTimeLogs: (taskId: string) => Promise<TaskTimeLog[]>
+  addTimeLog: (taskId: string, startedAt: string, endedAt: string) => Promise<void>
+  updateTimeLog: (logId: number, startedAt: string, endedAt: string) => Promise<void>
+  deleteTimeLog: (logId: number) => Promise<void>
+  resolveIdleTime: (keep: boolean) => Promise<void>
   getTodayTasks: () => Task[]
   getTodayTasksWithSessions: () => TaskWithActiveSession[]
   getTodayActiveTasks: () => Task[]
@@ -67,6 +152,40 @@ type TaskStore = {
 export const useTaskStore = create<TaskStore>((set, get) => ({
   tasks: [],
   tasksWithSessions: [],
+  timezone: null,
+  idlePausedPeriod: null,
+
+  setIdlePausedPeriod: (period: IdlePeriod | null) => set({ idlePausedPeriod: period }),
+
+  loadTimezone: async () => {
+    try {
+      const timezone = await invoke<string>("get_timezone")
+      set({ timezone })
+    } catch (error) {
+      console.error("Error loading timezone:", error)
+    }
+  },
+
+  // Fuso inválido sobe para o formulário mostrar
+  setTimezone: async (timezone: string) => {
+    try {
+      const saved = await invoke<string>("set_timezone", { timezone })
+      set({ timezone: saved })
+    } catch (error) {
+      console.error("Error setting timezone:", error)
+      throw error
+    }
+  },
+
+  getDailyTimeTotals: async (from: string, to: string) => {
+    try {
+      // Antes de loadTimezone o fuso é null e o backend usa o gravado nas configurações
+      return await invoke<DailyTimeTotal[]>("get_daily_time_totals", { from, to, timezone: get().timezone })
+    } catch (error) {
+      console.error("Error loading daily time totals:", error)
+      return []
+    }
+  },
 
   loadTasks: async () => {
     try {
@@ -85,7 +204,8 @@ export const useTaskStore = create<TaskStore>((set, get) => ({
         name: task.name,
         user: task.user,
         estimatedHours: task.estimated_hours,
-        scheduledDate: task.scheduled_date
+        scheduledDate: task.scheduled_date,
+        profileId: task.profile_id ?? null
       }
 
       console.log("Enviando dados do formulário para o Rust:", params)
@@ -102,6 +222,23 @@ export const useTaskStore = create<TaskStore>((set, get) => ({
     }
   },
 
+  updateTask: async (taskId: string, changes: TaskUpdate) => {
+    try {
+      await invoke<Task>("update_task", {
+        taskId,
+        name: changes.name,
+        user: changes.user,
+        estimatedHours: changes.estimated_hours,
+        scheduledDate: changes.scheduled_date
+      })
+      // Recarregar ambos os tipos de dados
+      await Promise.all([get().loadTasks(), get().loadTasksWithSessions()])
+    } catch (error) {
+      console.error("Error updating task:", error)
+      throw error
+    }
+  },
+
   startTask: async (taskId: string) => {
     try {
       await invoke("start_task", {
@@ -124,6 +261,11 @@ export const useTaskStore = create<TaskStore>((set, get) => ({
       // Recarregar ambos os tipos de dados para obter status atualizado
       await Promise.all([get().loadTasks(), get().loadTasksWithSessions()])
     } catch (error) {
+      // Outra tarefa em andamento: oferecer pausá-la e iniciar esta
+      if (isAppError(error, "AnotherTaskActive") && window.confirm(`${error.message}\n\nPausar a tarefa atual e iniciar esta?`)) {
+        await get().startTask(taskId)
+        return
+      }
       console.error("Error starting task without stopping others:", error)
     }
   },
@@ -144,6 +286,11 @@ export const useTaskStore = create<TaskStore>((set, get) => ({
       // Recarregar ambos os tipos de dados para obter status atualizado
       await Promise.all([get().loadTasks(), get().loadTasksWithSessions()])
     } catch (error) {
+      if (isAppError(error, "AnotherTaskActive") && window.confirm(`${error.message}\n\nPausar a tarefa atual e retomar esta?`)) {
+        await invoke("pause_task", { taskId: error.details?.active_task_id })
+        await get().resumeTask(taskId)
+        return
+      }
       console.error("Error resuming task:", error)
     }
   },
@@ -201,20 +348,93 @@ export const useTaskStore = create<TaskStore>((set, get) => ({
     }
   },
 
+  skipPomodoroSession: async (taskId: string) => {
+    try {
+      await invoke("skip_pomodoro_session", { taskId })
+      await Promise.all([get().loadTasks(), get().loadTasksWithSessions()])
+    } catch (error) {
+      console.error("Error skipping pomodoro session:", error)
+    }
+  },
+
+  extendPomodoroSession: async (taskId: string, seconds: number) => {
+    try {
+      await invoke("extend_pomodoro_session", { taskId, seconds })
+      await get().loadTasksWithSessions()
+    } catch (error) {
+      console.error("Error extending pomodoro session:", error)
+    }
+  },
+
+  restartPomodoroSession: async (taskId: string) => {
+    try {
+      await invoke("restart_pomodoro_session", { taskId })
+      await get().loadTasksWithSessions()
+    } catch (error) {
+      console.error("Error restarting pomodoro session:", error)
+    }
+  },
+
+  listTimeLogs: async (taskId: string) => {
+    try {
+      return await invoke<TaskTimeLog[]>("list_time_logs", { taskId })
+    } catch (error) {
+      console.error("Error listing time logs:", error)
+      return []
+    }
+  },
+
+  // Correções de tempo: erros de validação/sobreposição sobem para o formulário mostrar
+  addTimeLog: async (taskId: string, startedAt: string, endedAt: string) => {
+    try {
+      await invoke<TaskTimeLog>("add_time_log", { taskId, startedAt, endedAt })
+    } catch (error) {
+      console.error("Error adding time log:", error)
+      throw error
+    }
+  },
+
+  updateTimeLog: async (logId: number, startedAt: string, endedAt: string) => {
+    try {
+      await invoke<TaskTimeLog>("update_time_log", { logId, startedAt, endedAt })
+    } catch (error) {
+      console.error("Error updating time log:", error)
+      throw error
+    }
+  },
+
+  deleteTimeLog: async (logId: number) => {
+    try {
+      await invoke("delete_time_log", { logId })
+    } catch (error) {
+      console.error("Error deleting time log:", error)
+      throw error
+    }
+  },
+
+  resolveIdleTime: async (keep: boolean) => {
+    try {
+      await invoke("resolve_idle_time", { keep })
+      set({ idlePausedPeriod: null })
+    } catch (error) {
+      console.error("Error resolving idle time:", error)
+    }
+  },
+
   getTodayTasks: () => {
-    const today = new Date().toISOString().split("T")[0]
+    const today = todayInTimezone(get().timezone)
     const todayTasks = get().tasks.filter(task => task.scheduled_date === today)
     return todayTasks
   },
 
   getTodayTasksWithSessions: () => {
-    const today = new Date().toISOString().split("T")[0]
+    const today = todayInTimezone(get().timezone)
     const todayTasks = get().tasksWithSessions.filter(task => task.scheduled_date === today)
     return todayTasks
   },
 
   getTodayActiveTasks: () => {
-    const today = new Date().toISOString().split("T")[0]
+    const today = todayInTimezone(get().timezone)
     const todayTasks = get().tasks.filter(
       task => task.scheduled_date === today && task.status !== "completed"
     )
@@ -222,7 +442,7 @@ export const useTaskStore = create<TaskStore>((set, get) => ({
   },
 
   getTodayActiveTasksWithSessions: () => {
-    const today = new Date().toISOString().split("T")[0]
+    const today = todayInTimezone(get().timezone)
     const todayTasks = get().tasksWithSessions.filter(
       task => task.scheduled_date === today && task.status !== "completed"
     )
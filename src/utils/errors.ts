@@ -0,0 +1,40 @@
+// Formato dos erros devolvidos pelos comandos do backend (AppError)
+export type AppErrorCode =
+  | "TaskNotFound"
+  | "TaskAlreadyActive"
+  | "AnotherTaskActive"
+  | "TaskNotPaused"
+  | "InvalidTransition"
+  | "NoActiveSession"
+  | "SessionAlreadyActive"
+  | "ProfileNotFound"
+  | "DefaultProfileLocked"
+  | "TimeLogNotFound"
+  | "TimeLogInProgress"
+  | "TimeLogOverlap"
+  | "Validation"
+  | "UnsupportedSchemaVersion"
+  | "Migration"
+  | "Database"
+  | "Window"
+  | "System"
+
+export interface AppError {
+  code: AppErrorCode
+  message: string
+  details: Record<string, unknown> | null
+}
+
+export function isAppError(error: unknown, code?: AppErrorCode): error is AppError {
+  if (typeof error !== "object" || error === null || !("code" in error) || !("message" in error)) {
+    return false
+  }
+
+  return code === undefined || (error as AppError).code === code
+}
+
+export function errorMessage(error: unknown): string {
+  if (isAppError(error)) return error.message
+  if (error instanceof Error) return error.message
+  return String(error)
+}
@@ -14,3 +14,33 @@ export function formatTimeDisplay(duration: Duration): string {
     duration.isNegative ? "-" : ""
   }${formattedHours}:${formattedMinutes}:${formattedSeconds}`
 }
+
+// Dia escolhido no calendário (meia-noite local) como AAAA-MM-DD; toISOString voltaria um dia a leste de UTC
+export function formatCalendarDate(date: Date): string {
+  const year = date.getFullYear()
+  const month = String(date.getMonth() + 1).padStart(2, "0")
+  const day = String(date.getDate()).padStart(2, "0")
+  return `${year}-${month}-${day}`
+}
+
+// Data de hoje (AAAA-MM-DD) no fuso IANA informado; sem fuso, o do navegador
+export function todayInTimezone(timezone: string | null): string {
+  return new Intl.DateTimeFormat("en-CA", {
+    timeZone: timezone ?? undefined,
+    year: "numeric",
+    month: "2-digit",
+    day: "2-digit"
+  }).format(new Date())
+}
+
+// RFC3339 do backend para o valor de um <input type="datetime-local"> (hora local, com segundos)
+export function toDateTimeLocal(value: string): string {
+  const date = new Date(value)
+  const pad = (part: number) => String(part).padStart(2, "0")
+  return `${formatCalendarDate(date)}T${pad(date.getHours())}:${pad(date.getMinutes())}:${pad(date.getSeconds())}`
+}
+
+// Valor de um <input type="datetime-local"> (hora local) para RFC3339 em UTC
+export function fromDateTimeLocal(value: string): string {
+  return new Date(value).toISOString()
+}